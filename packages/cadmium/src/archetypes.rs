@@ -1,15 +1,18 @@
-use tsify::Tsify;
 use serde::{Deserialize, Serialize};
-use truck_modeling::Plane as TruckPlane;
 use truck_modeling::InnerSpace;
+use truck_modeling::Plane as TruckPlane;
+use truck_modeling::{Point3 as TruckPoint3, Vector3 as TruckVector3};
+use tsify::Tsify;
 
 use crate::sketch::Point2;
 
-#[derive(Tsify, Debug, Serialize, Deserialize)]
+// Edges of a solid are identified by their end points rather than by index, because
+// truck renumbers everything whenever the solid is rebuilt
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
 #[tsify(into_wasm_abi, from_wasm_abi)]
-pub enum PlaneDescription {
-    PlaneId(String),
-    SolidFace { solid_id: String, normal: Vector3 },
+pub struct EdgeDescription {
+    pub start: Point3,
+    pub end: Point3,
 }
 
 #[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
@@ -87,6 +90,24 @@ impl Plane {
         }
     }
 
+    // Builds a plane from an origin and two directions in it, which needn't be square
+    // to each other. The secondary direction is squared up to the primary one
+    pub fn from_vectors(
+        origin: TruckPoint3,
+        primary: TruckVector3,
+        secondary: TruckVector3,
+    ) -> Self {
+        let u = primary.normalize();
+        let n = u.cross(secondary).normalize();
+        let v = n.cross(u);
+        Plane {
+            origin: Point3::new(origin.x, origin.y, origin.z),
+            primary: Vector3::new(u.x, u.y, u.z),
+            secondary: Vector3::new(v.x, v.y, v.z),
+            tertiary: Vector3::new(n.x, n.y, n.z),
+        }
+    }
+
     pub fn project(&self, point: &Point3) -> Point2 {
         let minus_origin = point.minus(&self.origin);
         let x = minus_origin.dot(&self.primary);
@@ -95,9 +116,9 @@ impl Plane {
     }
 
     pub fn unproject(&self, point: &Point2) -> Point3 {
-        let x = self.origin.plus(self.primary.times(point.x));
-        let y = self.origin.plus(self.secondary.times(point.y));
-        x.plus(y).to_point3()
+        let x = self.primary.times(point.x);
+        let y = self.secondary.times(point.y);
+        self.origin.plus(x.plus(y)).to_point3()
     }
 }
 
@@ -205,3 +226,27 @@ pub struct Circle3 {
     pub radius: f64,
     pub top: u64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unproject_plane_away_from_origin() {
+        // the origin of the plane only counts once, however far away it is
+        let plane = Plane::new(
+            Point3::new(5.0, -2.0, 30.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        );
+        let point = plane.unproject(&Point2::new(3.0, 4.0));
+        assert!((point.x - 8.0).abs() < 1e-9);
+        assert!((point.y - 2.0).abs() < 1e-9);
+        assert!((point.z - 30.0).abs() < 1e-9);
+
+        let back = plane.project(&point);
+        assert!((back.x - 3.0).abs() < 1e-9);
+        assert!((back.y - 4.0).abs() < 1e-9);
+    }
+}
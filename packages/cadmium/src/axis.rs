@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use truck_modeling::{InnerSpace, Surface, Transform};
+use truck_modeling::{Point3 as TruckPoint3, Vector3 as TruckVector3};
+
+use crate::archetypes::{Point3, Vector3};
+use crate::error::CADmiumError;
+use crate::pattern::PatternAxis;
+use crate::point::curve_center;
+use crate::realization::Realization;
+use crate::solid::Solid;
+
+// A reference axis, worked out again every time the workbench is realized
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum Axis {
+    // from one point step to another, like "Point-1"
+    TwoPoints { first: String, second: String },
+    // a line segment in a sketch, like "Sketch-0"
+    SketchLine { sketch_id: String, line_id: u64 },
+    // where two planes cross. It points along the first plane's normal crossed with
+    // the second one's
+    PlaneIntersection { first: String, second: String },
+    // the center line of the round face of a solid that passes closest to the point.
+    // It may point either way along the face
+    CylindricalFace { solid_id: String, point: Point3 },
+}
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct RealAxis {
+    pub origin: Point3,
+    // always of unit length
+    pub direction: Vector3,
+    pub name: String,
+}
+
+impl Axis {
+    // Returns the origin and unit direction of the axis in 3D
+    pub fn resolve(&self, realized: &Realization) -> Result<(Point3, Vector3), CADmiumError> {
+        let truck_point = |p: &Point3| TruckPoint3::new(p.x, p.y, p.z);
+        let (origin, direction, source) = match self {
+            Axis::TwoPoints { first, second } => {
+                let point = |id: &String| {
+                    realized
+                        .points
+                        .get(id)
+                        .map(truck_point)
+                        .ok_or(CADmiumError::PointNotFound(id.clone()))
+                };
+                let start = point(first)?;
+                (start, point(second)? - start, first)
+            }
+            Axis::SketchLine { sketch_id, line_id } => {
+                return PatternAxis::SketchLine {
+                    sketch_id: sketch_id.clone(),
+                    line_id: *line_id,
+                }
+                .resolve(realized);
+            }
+            Axis::PlaneIntersection { first, second } => {
+                let plane = |id: &String| {
+                    let plane = &realized
+                        .planes
+                        .get(id)
+                        .ok_or(CADmiumError::PlaneNotFound(id.clone()))?
+                        .plane;
+                    let normal =
+                        TruckVector3::new(plane.tertiary.x, plane.tertiary.y, plane.tertiary.z);
+                    if normal.magnitude() < 1e-9 {
+                        return Err(CADmiumError::InvalidAxis(id.clone()));
+                    }
+                    let normal = normal.normalize();
+                    Ok((
+                        normal,
+                        normal.dot(truck_point(&plane.origin) - TruckPoint3::new(0.0, 0.0, 0.0)),
+                    ))
+                };
+                let (n1, h1) = plane(first)?;
+                let (n2, h2) = plane(second)?;
+                let direction = n1.cross(n2);
+                let length2 = direction.magnitude2();
+                if length2 < 1e-18 {
+                    return Err(CADmiumError::InvalidAxis(first.clone()));
+                }
+
+                // the point of the line that is closest to the world origin
+                let origin = TruckPoint3::new(0.0, 0.0, 0.0)
+                    + (n2.cross(direction) * h1 + direction.cross(n1) * h2) / length2;
+                (origin, direction, first)
+            }
+            Axis::CylindricalFace { solid_id, point } => {
+                let solid = realized
+                    .solids
+                    .get(solid_id)
+                    .ok_or(CADmiumError::SolidNotFound(solid_id.clone()))?;
+                let (origin, direction) = cylinder_axis(solid, truck_point(point))?;
+                (origin, direction, solid_id)
+            }
+        };
+
+        if direction.magnitude() < 1e-9 {
+            return Err(CADmiumError::InvalidAxis(source.clone()));
+        }
+        let direction = direction.normalize();
+        Ok((
+            Point3::new(origin.x, origin.y, origin.z),
+            Vector3::new(direction.x, direction.y, direction.z),
+        ))
+    }
+}
+
+// Finds the center line of the round face of the solid that comes closest to near. A
+// face is round when it was made by revolving a curve, or by extruding one that is an
+// arc of a circle, and the axis comes straight from that surface
+fn cylinder_axis(
+    solid: &Solid,
+    near: TruckPoint3,
+) -> Result<(TruckPoint3, TruckVector3), CADmiumError> {
+    let mut closest: Option<(f64, TruckPoint3, TruckVector3)> = None;
+    for face in solid.truck_solid.face_iter() {
+        let (origin, direction) = match face.surface() {
+            Surface::RevolutedCurve(surface) => {
+                let transform = surface.transform();
+                let revolved = surface.entity();
+                (
+                    transform.transform_point(revolved.origin()),
+                    transform.transform_vector(revolved.axis()),
+                )
+            }
+            Surface::ExtrudedCurve(surface) => match curve_center(surface.entity_curve()) {
+                Some(center) => (center, surface.extruding_vector()),
+                None => continue,
+            },
+            _ => continue,
+        };
+        if direction.magnitude() < 1e-9 {
+            continue;
+        }
+        let direction = direction.normalize();
+
+        // how far near is from the face, measured across the axis and along it, against
+        // the span that the face's vertices cover
+        let place = |p: TruckPoint3| {
+            let along = (p - origin).dot(direction);
+            ((p - origin - direction * along).magnitude(), along)
+        };
+        let (mut low, mut high) = ((f64::MAX, f64::MAX), (f64::MIN, f64::MIN));
+        for wire in face.boundaries() {
+            for vertex in wire.vertex_iter() {
+                let (across, along) = place(vertex.point());
+                low = (low.0.min(across), low.1.min(along));
+                high = (high.0.max(across), high.1.max(along));
+            }
+        }
+        let (across, along) = place(near);
+        let outside = |v: f64, low: f64, high: f64| (low - v).max(v - high).max(0.0);
+        let distance = outside(across, low.0, high.0).hypot(outside(along, low.1, high.1));
+
+        if closest.map_or(true, |(d, _, _)| distance < d) {
+            closest = Some((distance, origin, direction));
+        }
+    }
+
+    closest
+        .map(|(_, origin, direction)| (origin, direction))
+        .ok_or(CADmiumError::UnsupportedShape(solid.name.clone()))
+}
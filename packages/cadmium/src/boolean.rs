@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum BooleanOperation {
+    Union,
+    Subtract,
+    Intersect,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct Boolean {
+    // the solid that gets changed, which keeps its name
+    pub target_id: String,
+    // combined with the target one at a time, in this order
+    pub tool_ids: Vec<String>,
+    pub operation: BooleanOperation,
+    // when unset the tools are used up and removed from the realization
+    pub keep_tools: bool,
+}
+
+impl Boolean {
+    pub fn new(
+        target_id: String,
+        tool_ids: Vec<String>,
+        operation: BooleanOperation,
+        keep_tools: bool,
+    ) -> Self {
+        Boolean {
+            target_id,
+            tool_ids,
+            operation,
+            keep_tools,
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use std::f64::consts::PI;
+
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use truck_modeling::{InnerSpace, Solid as TruckSolid, Vector3 as TruckVector3};
+
+use crate::archetypes::{EdgeDescription, Vector3};
+use crate::error::CADmiumError;
+use crate::extrusion::polygon_prism;
+use crate::solid::EdgeCorner;
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct Chamfer {
+    pub solid_id: String,
+    pub edges: Vec<EdgeDescription>,
+    pub mode: ChamferMode,
+}
+
+impl Chamfer {
+    pub fn new(solid_id: String, edges: Vec<EdgeDescription>, mode: ChamferMode) -> Self {
+        Chamfer {
+            solid_id,
+            edges,
+            mode,
+        }
+    }
+}
+
+// Distances are measured across the faces, from the edge to where the chamfer meets
+// the face. The "first" face at each edge is the one whose outward normal points most
+// nearly along first_face_normal, so one normal can pick out, say, the top face along
+// every edge around it.
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum ChamferMode {
+    EqualDistance {
+        distance: f64,
+    },
+    TwoDistances {
+        distance_0: f64,
+        distance_1: f64,
+        first_face_normal: Vector3,
+    },
+    // angle in radians, measured from the first face
+    DistanceAngle {
+        distance: f64,
+        angle: f64,
+        first_face_normal: Vector3,
+    },
+}
+
+impl ChamferMode {
+    // Returns how far the chamfer reaches across each of the two faces
+    pub fn distances(&self, corner: &EdgeCorner) -> Result<(f64, f64), CADmiumError> {
+        match self {
+            ChamferMode::EqualDistance { distance } => Ok((*distance, *distance)),
+            ChamferMode::TwoDistances {
+                distance_0,
+                distance_1,
+                ..
+            } => Ok((*distance_0, *distance_1)),
+            ChamferMode::DistanceAngle {
+                distance, angle, ..
+            } => {
+                // law of sines on the triangle that gets cut away
+                let corner_angle = corner.inward.0.dot(corner.inward.1).clamp(-1.0, 1.0).acos();
+                if corner_angle + angle >= PI {
+                    return Err(CADmiumError::GeometryConstructionFailed(
+                        corner.solid_name.clone(),
+                    ));
+                }
+                Ok((
+                    *distance,
+                    distance * angle.sin() / (corner_angle + angle).sin(),
+                ))
+            }
+        }
+    }
+
+    // The corner with its two faces swapped around if needed, so that the first one is
+    // the face the mode measures its first distance across
+    pub fn order_faces(&self, corner: &EdgeCorner) -> Result<EdgeCorner, CADmiumError> {
+        let normal = match self {
+            ChamferMode::EqualDistance { .. } => return Ok(corner.clone()),
+            ChamferMode::TwoDistances {
+                first_face_normal, ..
+            }
+            | ChamferMode::DistanceAngle {
+                first_face_normal, ..
+            } => TruckVector3::new(
+                first_face_normal.x,
+                first_face_normal.y,
+                first_face_normal.z,
+            ),
+        };
+
+        let (normal_0, normal_1) = corner.normals;
+        let (facing_0, facing_1) = (normal_0.dot(normal), normal_1.dot(normal));
+        if (facing_0 - facing_1).abs() < 1e-6 {
+            // the normal doesn't tell the two faces apart
+            return Err(CADmiumError::AmbiguousFace(corner.solid_name.clone()));
+        }
+        if facing_0 > facing_1 {
+            return Ok(corner.clone());
+        }
+        Ok(EdgeCorner {
+            normals: (normal_1, normal_0),
+            inward: (corner.inward.1, corner.inward.0),
+            ..corner.clone()
+        })
+    }
+}
+
+// Builds the triangular prism that has to be taken away from (convex edge) or added to
+// (concave edge) the body to bevel the given edge
+pub fn chamfer_tool(corner: &EdgeCorner, mode: &ChamferMode) -> Result<TruckSolid, CADmiumError> {
+    let corner = &mode.order_faces(corner)?;
+    let (distance_0, distance_1) = mode.distances(corner)?;
+    if distance_0 <= 0.0 || distance_1 <= 0.0 {
+        return Err(CADmiumError::GeometryConstructionFailed(
+            corner.solid_name.clone(),
+        ));
+    }
+
+    // faces that lie flat against each other leave no corner to cut off
+    let (inward_0, inward_1) = corner.inward;
+    let bisector = inward_0 + inward_1;
+    if bisector.magnitude() < 1e-9 {
+        return Err(CADmiumError::UnsupportedEdge(corner.solid_name.clone()));
+    }
+    let along = (corner.end - corner.start).normalize();
+    let length = (corner.end - corner.start).magnitude();
+    let reach = distance_0.max(distance_1);
+
+    // run the tool a little past both ends of the edge so that its caps are never
+    // coplanar with the faces at the ends of the edge
+    let start = corner.start - along * reach;
+    let point_0 = start + inward_0 * distance_0;
+    let point_1 = start + inward_1 * distance_1;
+
+    // push the tip of the triangle past the edge so the tool doesn't share any faces with the solid
+    let apex = start - bisector.normalize() * reach;
+
+    polygon_prism(&[apex, point_0, point_1], along * (length + 2.0 * reach)).ok_or(
+        CADmiumError::GeometryConstructionFailed(corner.solid_name.clone()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use truck_modeling::Point3 as TruckPoint3;
+
+    use super::*;
+
+    #[test]
+    fn distance_angle_on_square_edge() {
+        // the top front edge of a box: one face points up, the other points forward
+        let corner = EdgeCorner {
+            solid_name: "Box".to_owned(),
+            start: TruckPoint3::new(0.0, 0.0, 1.0),
+            end: TruckPoint3::new(1.0, 0.0, 1.0),
+            normals: (
+                TruckVector3::new(0.0, 0.0, 1.0),
+                TruckVector3::new(0.0, -1.0, 0.0),
+            ),
+            inward: (
+                TruckVector3::new(0.0, 1.0, 0.0),
+                TruckVector3::new(0.0, 0.0, -1.0),
+            ),
+            convex: true,
+        };
+
+        let mode = ChamferMode::DistanceAngle {
+            distance: 2.0,
+            angle: PI / 4.0,
+            first_face_normal: Vector3::new(0.0, 0.0, 1.0),
+        };
+        let (distance_0, distance_1) = mode.distances(&corner).unwrap();
+        assert!((distance_0 - 2.0).abs() < 1e-9);
+        assert!((distance_1 - 2.0).abs() < 1e-9);
+
+        let mode = ChamferMode::DistanceAngle {
+            distance: 1.0,
+            angle: PI / 3.0,
+            first_face_normal: Vector3::new(0.0, 0.0, 1.0),
+        };
+        let (_, distance_1) = mode.distances(&corner).unwrap();
+        assert!((distance_1 - 3.0_f64.sqrt()).abs() < 1e-9);
+
+        // asking for the front face first swaps the faces around, whatever order they
+        // were found in
+        let front_first = ChamferMode::TwoDistances {
+            distance_0: 1.0,
+            distance_1: 2.0,
+            first_face_normal: Vector3::new(0.0, -1.0, 0.0),
+        }
+        .order_faces(&corner)
+        .unwrap();
+        assert!((front_first.normals.0.y + 1.0).abs() < 1e-9);
+        assert!((front_first.inward.0.z + 1.0).abs() < 1e-9);
+
+        // a normal square to both faces can't pick one
+        let sideways = ChamferMode::TwoDistances {
+            distance_0: 1.0,
+            distance_1: 2.0,
+            first_face_normal: Vector3::new(1.0, 0.0, 0.0),
+        };
+        assert!(sideways.order_faces(&corner).is_err());
+    }
+
+    #[test]
+    fn flat_edge_is_rejected() {
+        // two faces that carry on flat from one another across the edge
+        let corner = EdgeCorner {
+            solid_name: "Box".to_owned(),
+            start: TruckPoint3::new(0.0, 0.0, 1.0),
+            end: TruckPoint3::new(1.0, 0.0, 1.0),
+            normals: (
+                TruckVector3::new(0.0, 0.0, 1.0),
+                TruckVector3::new(0.0, 0.0, 1.0),
+            ),
+            inward: (
+                TruckVector3::new(0.0, 1.0, 0.0),
+                TruckVector3::new(0.0, -1.0, 0.0),
+            ),
+            convex: false,
+        };
+        let mode = ChamferMode::EqualDistance { distance: 1.0 };
+        assert!(chamfer_tool(&corner, &mode).is_err());
+    }
+}
@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use truck_modeling::Vector3 as TruckVector3;
+use truck_modeling::{InnerSpace, Point3 as TruckPoint3, Solid as TruckSolid, Surface};
+
+use crate::archetypes::Vector3;
+use crate::error::CADmiumError;
+use crate::extrusion::polygon_prism;
+use crate::project::RealPlane;
+use crate::solid::Solid;
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct Draft {
+    pub solid_id: String,
+    // the outward normals of the planar faces to tilt
+    pub face_normals: Vec<Vector3>,
+    // faces hinge about the line where they cross this plane, and the pull direction
+    // is the plane's normal
+    pub neutral_plane_id: String,
+    // in radians. Positive angles tilt the faces in toward the body above the neutral plane
+    pub angle: f64,
+}
+
+impl Draft {
+    pub fn new(
+        solid_id: String,
+        face_normals: Vec<Vector3>,
+        neutral_plane_id: String,
+        angle: f64,
+    ) -> Self {
+        Draft {
+            solid_id,
+            face_normals,
+            neutral_plane_id,
+            angle,
+        }
+    }
+}
+
+// Builds the wedges that tilt each selected face about its hinge line. Each tool comes
+// with whether it gets cut away (true) or added on (false)
+pub fn draft_tools(
+    solid: &Solid,
+    draft: &Draft,
+    neutral: &RealPlane,
+) -> Result<Vec<(bool, TruckSolid)>, CADmiumError> {
+    let pull = TruckVector3::new(
+        neutral.plane.tertiary.x,
+        neutral.plane.tertiary.y,
+        neutral.plane.tertiary.z,
+    )
+    .normalize();
+    let neutral_origin = TruckPoint3::new(
+        neutral.plane.origin.x,
+        neutral.plane.origin.y,
+        neutral.plane.origin.z,
+    );
+
+    let mut tools = vec![];
+    for face_normal in &draft.face_normals {
+        let wanted = TruckVector3::new(face_normal.x, face_normal.y, face_normal.z).normalize();
+
+        let mut found = false;
+        for face in solid.truck_solid.face_iter() {
+            let plane = match face.oriented_surface() {
+                Surface::Plane(plane) => plane,
+                _ => continue,
+            };
+            let normal = plane.normal();
+            if (normal - wanted).magnitude() > 0.0001 {
+                continue;
+            }
+            found = true;
+
+            // faces that are parallel to the neutral plane have no hinge to tilt about
+            let k = normal.dot(pull);
+            if k.abs() > 1.0 - 1e-6 {
+                return Err(CADmiumError::GeometryConstructionFailed(solid.name.clone()));
+            }
+
+            // a point on the line where the face's plane crosses the neutral plane
+            let d_face = normal.dot(plane.origin() - TruckPoint3::new(0.0, 0.0, 0.0));
+            let d_neutral = pull.dot(neutral_origin - TruckPoint3::new(0.0, 0.0, 0.0));
+            let hinge = TruckPoint3::new(0.0, 0.0, 0.0)
+                + (normal * (d_face - d_neutral * k) + pull * (d_neutral - d_face * k))
+                    / (1.0 - k * k);
+            let along = normal.cross(pull).normalize();
+
+            // up is the pull direction flattened into the face, tilted is where up goes
+            // once the face has been drafted
+            let up = (pull - normal * k).normalize();
+            let tilted = up * draft.angle.cos() - normal * draft.angle.sin();
+
+            let points: Vec<TruckPoint3> = face.edge_iter().map(|e| e.front().point()).collect();
+            let heights = points.iter().map(|p| (p - hinge).dot(up));
+            let above = heights.clone().fold(0.0, f64::max);
+            let below = heights.fold(0.0, f64::min);
+            let (first, last) = points
+                .iter()
+                .map(|p| (p - hinge).dot(along))
+                .fold((f64::MAX, f64::MIN), |(lo, hi), t| (lo.min(t), hi.max(t)));
+
+            // wedges that get cut away run past every edge of the face so they never end
+            // flush with it. Wedges that get added end exactly at the face's ends, or
+            // they would stick out into the faces next to it
+            let margin = 0.1 * (above - below).max(last - first);
+            let slope = 1.0 / draft.angle.cos();
+
+            for height in [above, below] {
+                if height == 0.0 || draft.angle == 0.0 {
+                    continue;
+                }
+
+                // where the tilted face passes inside the old one the wedge between them
+                // gets cut away, and it can overshoot into empty space. Elsewhere the
+                // wedge gets added and has to stop exactly at the face's extent
+                let cut = (height > 0.0) == (draft.angle > 0.0);
+                let (wedge, sweep) = if cut {
+                    let start = hinge + along * (first - margin);
+                    let reach = height + margin * height.signum();
+                    (
+                        vec![
+                            start,
+                            start + tilted * (reach * slope),
+                            start + up * reach + normal * margin,
+                            start + normal * margin,
+                        ],
+                        along * (last - first + 2.0 * margin),
+                    )
+                } else {
+                    let start = hinge + along * first;
+                    (
+                        vec![
+                            start,
+                            start + up * height,
+                            start + tilted * (height * slope),
+                        ],
+                        along * (last - first),
+                    )
+                };
+
+                let tool = polygon_prism(&wedge, sweep)
+                    .ok_or(CADmiumError::GeometryConstructionFailed(solid.name.clone()))?;
+                tools.push((cut, tool));
+            }
+        }
+
+        if !found {
+            return Err(CADmiumError::FaceNotFound(solid.name.clone()));
+        }
+    }
+
+    Ok(tools)
+}
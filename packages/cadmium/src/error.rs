@@ -30,6 +30,53 @@ pub enum CADmiumError {
 	#[error("The {0} with ID {1} has an end point that doesn't exist in the current sketch")]
 	SketchFeatureMissingEnd(SketchFeatureType, u64),
 
+	// Solid feature errors
+	#[error("The solid {0} was not found")]
+	SolidNotFound(String),
+	#[error("The solid {0} has no edge between the selected points")]
+	EdgeNotFound(String),
+	#[error("The solid {0} has no planar face with the selected normal")]
+	FaceNotFound(String),
+	#[error("The selection matches more than one face of solid {0}")]
+	AmbiguousFace(String),
+	#[error("The selected edge of solid {0} is not a straight edge between two planar faces")]
+	UnsupportedEdge(String),
+	#[error("The solid {0} has faces or edges that this feature can't handle")]
+	UnsupportedShape(String),
+	#[error("Failed to build the geometry for {0}")]
+	GeometryConstructionFailed(String),
+	#[error("The boolean operation on solid {0} failed")]
+	BooleanFailed(String),
+	#[error("The counterbore or countersink of a hole in solid {0} doesn't fit the hole")]
+	InvalidHoleProfile(String),
+	#[error("The point {0} was not found")]
+	PointNotFound(String),
+	#[error("The plane {0} was not found")]
+	PlaneNotFound(String),
+	#[error("The axis of {0} doesn't exist or has no length")]
+	InvalidAxis(String),
+	#[error("The path in sketch {0} isn't a chain of connected lines and arcs")]
+	InvalidPath(String),
+	#[error("The sketch {0} has no open chain through the selected segment")]
+	ChainNotFound(String),
+	#[error("A wall in {0} is too thick to follow one of the arcs of its chain")]
+	WallTooThick(String),
+	#[error("The plane described by {0} can't be worked out")]
+	InvalidPlane(String),
+	#[error("The split tool doesn't cut through the solid {0}")]
+	SplitMissesSolid(String),
+	#[error("The chain in sketch {0} bends both ways or turns back on itself too far to split with")]
+	SplitChainBends(String),
+	#[error("The rib on solid {0} doesn't run into the solid")]
+	RibMissesSolid(String),
+	#[error("There is no face for the extrusion of sketch {0} to end at")]
+	EndFaceNotFound(String),
+	#[error("There are no solids ahead of the extrusion of sketch {0} for it to go through")]
+	NothingToExtrudeThrough(String),
+	#[error("The point {0} is behind where the extrusion starts")]
+	PointBehindExtrusion(String),
+	#[error("The draft angle of {0} pulls the profile in until it vanishes")]
+	DraftCollapsesProfile(String),
 
 	#[error("This function is not implemented yet")]
 	NotImplemented,
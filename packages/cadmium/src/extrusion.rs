@@ -13,12 +13,17 @@ use truck_shapeops::ShapeOpsSurface;
 use truck_topology::Shell;
 
 use crate::archetypes::{Point3, Vector3};
+use crate::error::CADmiumError;
 use crate::project::{RealPlane, RealSketch};
-use crate::sketch::{arc_to_points, Face, Sketch};
+use crate::realization::Realization;
+use crate::sketch::{arc_to_points, Face, Point2, Ring, Segment, Sketch};
+use crate::solid::{split_shells, Solid};
+use crate::sweep::PathSegment;
 
-use truck_modeling::{Plane, Point3 as TruckPoint3, Surface};
+use std::collections::HashMap;
 
-use truck_topology::Solid as TruckSolid;
+use truck_modeling::{builder, Plane, Point3 as TruckPoint3, Surface, Vector3 as TruckVector3};
+use truck_modeling::{Solid as TruckModelingSolid, Wire};
 
 #[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
 #[tsify(into_wasm_abi, from_wasm_abi)]
@@ -29,6 +34,60 @@ pub struct Extrusion {
     pub offset: f64,
     pub direction: Direction,
     pub mode: ExtrusionMode,
+    // taper of the side walls in radians. Positive angles lean the walls in toward
+    // the profile as the extrusion goes on, negative angles flare them out
+    #[serde(default)]
+    pub draft_angle: f64,
+    // where the extrusion stops. Only Blind uses offset
+    #[serde(default)]
+    pub end: ExtrusionEnd,
+    // if positive, the extrusion also goes this far against its direction, starting
+    // from the sketch plane instead of from offset
+    #[serde(default)]
+    pub second_length: f64,
+    // if set, open chains of the sketch are thickened into walls and extruded too
+    #[serde(default)]
+    pub thin: Option<ThinWall>,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct ThinWall {
+    // a line or arc from each of the sketch's open chains that should be thickened
+    pub segments: Vec<PathSegment>,
+    pub thickness: f64,
+    pub side: WallSide,
+}
+
+// Which side of a chain its wall grows on, looking along the chain from its start
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum WallSide {
+    // to the left
+    OneSide,
+    // to the right
+    OtherSide,
+    // half to each side
+    MidPlane,
+}
+
+impl ThinWall {
+    pub fn new(segments: Vec<PathSegment>, thickness: f64, side: WallSide) -> Self {
+        ThinWall {
+            segments,
+            thickness,
+            side,
+        }
+    }
+
+    // How far each edge of a wall sits to the left of its chain, as (left, right)
+    pub fn offsets(&self) -> (f64, f64) {
+        match self.side {
+            WallSide::OneSide => (self.thickness, 0.0),
+            WallSide::OtherSide => (0.0, -self.thickness),
+            WallSide::MidPlane => (self.thickness / 2.0, -self.thickness / 2.0),
+        }
+    }
 }
 
 #[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
@@ -37,6 +96,8 @@ pub enum ExtrusionMode {
     New,
     Add(Vec<String>),
     Remove(Vec<String>),
+    // keeps only the volume the new solid shares with each of these solids
+    Intersect(Vec<String>),
 }
 
 impl Extrusion {
@@ -55,8 +116,185 @@ impl Extrusion {
             offset,
             direction,
             mode,
+            draft_angle: 0.0,
+            end: ExtrusionEnd::Blind,
+            second_length: 0.0,
+            thin: None,
         }
     }
+
+    // The direction the extrusion goes in. Lengths and offsets are multiples of it
+    pub fn direction_vector(&self, plane: &RealPlane) -> Vector3 {
+        match &self.direction {
+            Direction::Normal => plane.plane.tertiary.clone(),
+            Direction::NegativeNormal => plane.plane.tertiary.times(-1.0),
+            Direction::Specified(vector) => vector.clone(),
+        }
+    }
+
+    // Works out the stretch that the extrusion covers as (start, end), measured along
+    // its direction from the sketch plane. The end conditions that refer to other
+    // geometry are measured along the line through the middle of the profile.
+    pub fn extent(
+        &self,
+        plane: &RealPlane,
+        sketch: &RealSketch,
+        realized: &Realization,
+    ) -> Result<(f64, f64), CADmiumError> {
+        let direction = self.direction_vector(plane);
+        let direction = TruckVector3::new(direction.x, direction.y, direction.z);
+        let center = self.profile_center(sketch);
+        let along = |p: TruckPoint3| (p - center).dot(direction) / direction.dot(direction);
+        let start = if self.second_length > 0.0 {
+            -self.second_length
+        } else {
+            self.offset
+        };
+
+        // where the line through the profile's middle crosses a planar face, if it does
+        let crossing = |face: &truck_modeling::Face, check_extent: bool| -> Option<f64> {
+            let p = match face.oriented_surface() {
+                Surface::Plane(p) => p,
+                _ => return None,
+            };
+            let denominator = p.normal().dot(direction);
+            if denominator.abs() < 1e-9 {
+                return None;
+            }
+            let t = p.normal().dot(p.origin() - center) / denominator;
+
+            let hit = center + direction * t;
+            let (low, high) = face_extent(face);
+            let epsilon = 1e-6;
+            let inside = hit.x > low.x - epsilon
+                && hit.y > low.y - epsilon
+                && hit.z > low.z - epsilon
+                && hit.x < high.x + epsilon
+                && hit.y < high.y + epsilon
+                && hit.z < high.z + epsilon;
+            (inside || !check_extent).then_some(t)
+        };
+
+        match &self.end {
+            ExtrusionEnd::Blind => Ok((start, self.length)),
+            ExtrusionEnd::Symmetric => Ok((-self.length / 2.0, self.length / 2.0)),
+            ExtrusionEnd::ThroughAll => {
+                let scope: Vec<&Solid> = match &self.mode {
+                    ExtrusionMode::New => realized.solids.values().collect(),
+                    ExtrusionMode::Add(names)
+                    | ExtrusionMode::Remove(names)
+                    | ExtrusionMode::Intersect(names) => names
+                        .iter()
+                        .filter_map(|name| realized.solids.get(name))
+                        .collect(),
+                };
+                let furthest = scope
+                    .iter()
+                    .flat_map(|solid| solid.truck_solid.vertex_iter())
+                    .map(|vertex| along(vertex.point()))
+                    .fold(start, f64::max);
+                if furthest - start < 1e-9 {
+                    return Err(CADmiumError::NothingToExtrudeThrough(
+                        self.sketch_id.clone(),
+                    ));
+                }
+
+                // overshoot a little so the end never lands right on a face
+                Ok((start, furthest + 0.1 * (furthest - start) + 1e-3))
+            }
+            ExtrusionEnd::UpToNext => realized
+                .solids
+                .values()
+                .flat_map(|solid| solid.truck_solid.face_iter())
+                .filter_map(|face| crossing(face, true))
+                .filter(|t| *t > start.max(0.0) + 1e-6)
+                .min_by(|a, b| a.total_cmp(b))
+                .map(|t| (start, t))
+                .ok_or(CADmiumError::EndFaceNotFound(self.sketch_id.clone())),
+            ExtrusionEnd::UpToFace { solid_id, normal } => {
+                let solid = realized
+                    .solids
+                    .get(solid_id)
+                    .ok_or(CADmiumError::SolidNotFound(solid_id.clone()))?;
+                let normal = TruckVector3::new(normal.x, normal.y, normal.z);
+
+                // the face's plane counts, even where the face itself doesn't reach
+                solid
+                    .truck_solid
+                    .face_iter()
+                    .filter(|face| match face.oriented_surface() {
+                        Surface::Plane(p) => (p.normal() - normal).magnitude() < 0.0001,
+                        _ => false,
+                    })
+                    .find_map(|face| crossing(face, false))
+                    .map(|t| (start, t))
+                    .ok_or(CADmiumError::FaceNotFound(solid_id.clone()))
+            }
+            ExtrusionEnd::UpToPoint { point_id } => {
+                let point = realized
+                    .points
+                    .get(point_id)
+                    .ok_or(CADmiumError::PointNotFound(point_id.clone()))?;
+                let end = along(TruckPoint3::new(point.x, point.y, point.z));
+                if end - start < 1e-9 {
+                    return Err(CADmiumError::PointBehindExtrusion(point_id.clone()));
+                }
+                Ok((start, end))
+            }
+        }
+    }
+
+    // The average of the corners (or centers, for circles) of the selected faces' outlines
+    // and of the points along any thickened chains
+    fn profile_center(&self, sketch: &RealSketch) -> TruckPoint3 {
+        let mut point_ids: Vec<u64> = self
+            .face_ids
+            .iter()
+            .filter_map(|face_id| sketch.faces.get(*face_id as usize))
+            .flat_map(|face| match &face.exterior {
+                Ring::Circle(circle) => vec![circle.center],
+                Ring::Segments(segments) => segments.iter().map(|s| s.get_start()).collect(),
+            })
+            .collect();
+        if let Some(thin) = &self.thin {
+            point_ids.extend(
+                thin.segments
+                    .iter()
+                    .filter_map(|segment| sketch.chain_through(segment))
+                    .flat_map(|chain| chain.iter().map(|s| s.get_end())),
+            );
+        }
+
+        let mut sum = TruckVector3::new(0.0, 0.0, 0.0);
+        for point_id in point_ids.iter() {
+            let p = &sketch.points[point_id];
+            sum += TruckVector3::new(p.x, p.y, p.z);
+        }
+        TruckPoint3::new(0.0, 0.0, 0.0) + sum / point_ids.len().max(1) as f64
+    }
+}
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize, Default)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum ExtrusionEnd {
+    // exactly from offset to length
+    #[default]
+    Blind,
+    // length in total, split evenly across both sides of the sketch plane
+    Symmetric,
+    // far enough to get through every solid in the merge scope, or every solid for New
+    ThroughAll,
+    // to the first face of any solid that the extrusion runs into
+    UpToNext,
+    // to the plane of a flat face, picked by its outward normal
+    UpToFace {
+        solid_id: String,
+        normal: Vector3,
+    },
+    // to the height of a point step, like "Point-0"
+    UpToPoint {
+        point_id: String,
+    },
 }
 
 #[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
@@ -268,70 +506,491 @@ pub fn find_transit(
     transit_3d
 }
 
-pub fn fuse<C: ShapeOpsCurve<S> + std::fmt::Debug, S: ShapeOpsSurface + std::fmt::Debug>(
-    solid0: &TruckSolid<TruckPoint3, C, Surface>,
-    solid1: &TruckSolid<TruckPoint3, C, Surface>,
-) -> Option<TruckSolid<TruckPoint3, C, Surface>> {
-    println!("Okay let's fuse!");
-
-    let solid0_boundaries = solid0.boundaries();
-    let solid1_boundaries = solid1.boundaries();
-    assert!(solid0_boundaries.len() == 1);
-    assert!(solid1_boundaries.len() == 1);
-
-    let boundary0 = &solid0_boundaries[0];
-    let boundary1 = &solid1_boundaries[0];
-    let fusable_faces = find_coplanar_face_pairs(boundary0, boundary1, true);
-    assert!(fusable_faces.len() == 1);
-    let fusable_faces = fusable_faces[0];
-    // TODO: support the case where more than one is fusable
-    println!("fusable_faces: {:?}", fusable_faces);
-
-    let secondary_mergeable_faces = find_coplanar_face_pairs(boundary0, boundary1, false);
-    println!("secondary_mergeable_faces: {:?}", secondary_mergeable_faces);
-
-    // There's only one fused solid at the end. Create it by cloning solid0
-    // and then removing the fusable face from it.
-    let mut combined = boundary0.clone();
-    combined.remove(fusable_faces.0);
-
-    // Meanwhile, make a copy of solid1 and remove the fusable face from it too.
-    let mut boundary1_copy = boundary1.clone();
-    boundary1_copy.remove(fusable_faces.1);
-
-    // Then, add every face from solid1 to the combined solid.
-    combined.append(&mut boundary1_copy);
-
-    // Lastly, merge the two fusable faces together. This is complicated because
-    // one might be bigger than the other, or they might be the same size, or
-    // they might overlap somewhat. We'll need to figure out how to merge them.
-    // println!("How do I merge these two? {:?}", fusable_faces);
-    // println!("First:");
-    // for edge in boundary0[fusable_faces.0].edge_iter() {
-    //     println!(
-    //         "Edge: {:?} to {:?}",
-    //         edge.front().get_point(),
-    //         edge.back().get_point()
-    //     );
-    // }
-    let mut outer_face = boundary0[fusable_faces.0].clone();
-    let inner_face = boundary1[fusable_faces.1].clone();
-    outer_face.add_boundary(inner_face.boundaries().first().unwrap().clone());
-
-    // Then add that merged face to the solid and we've fused!
-    combined.push(outer_face);
-
-    // After that, we need to merge the secondary_mergeable_faces together.
-    for (face_0_idx, face_1_idx) in secondary_mergeable_faces {
-        let mut face_0 = boundary0[face_0_idx].clone();
-        let face_1 = boundary1[face_1_idx].clone();
-        face_0.add_boundary(face_1.boundaries().first().unwrap().clone());
-        combined.push(face_0);
-    }
-
-    // And then we're done!
-    // None
-    Some(TruckSolid::new(vec![combined]))
+// Sweeps the polygon through the given points along vector. The points may wind either way
+pub fn polygon_prism(points: &[TruckPoint3], vector: TruckVector3) -> Option<TruckModelingSolid> {
+    // tsweep wants the face normal to point along the sweep direction
+    let mut area_normal = TruckVector3::new(0.0, 0.0, 0.0);
+    for i in 1..points.len().saturating_sub(1) {
+        area_normal += (points[i] - points[0]).cross(points[i + 1] - points[0]);
+    }
+    let mut points = points.to_vec();
+    if area_normal.dot(vector) < 0.0 {
+        points.reverse();
+    }
+
+    let vertices: Vec<_> = points.iter().map(|p| builder::vertex(*p)).collect();
+    let wire: Wire = (0..vertices.len())
+        .map(|i| builder::line(&vertices[i], &vertices[(i + 1) % vertices.len()]))
+        .collect();
+
+    let face = builder::try_attach_plane(&[wire]).ok()?;
+    Some(builder::tsweep(&face, vector))
+}
+
+pub fn ring_is_counterclockwise(ring: &Ring, points: &HashMap<u64, Point2>) -> bool {
+    match ring {
+        // circles are always swept counterclockwise around the plane normal
+        Ring::Circle(_) => true,
+        Ring::Segments(segments) => {
+            let mut outline: Vec<Point2> = vec![];
+            for segment in segments {
+                match segment {
+                    Segment::Line(line) => outline.push(points[&line.start].clone()),
+                    Segment::Arc(arc) => {
+                        let mut arc_points = arc_to_points(
+                            &points[&arc.start],
+                            &points[&arc.end],
+                            &points[&arc.center],
+                            arc.clockwise,
+                        );
+                        arc_points.pop();
+                        outline.append(&mut arc_points);
+                    }
+                }
+            }
+
+            let mut signed_area = 0.0;
+            for (i, a) in outline.iter().enumerate() {
+                let b = &outline[(i + 1) % outline.len()];
+                signed_area += a.x * b.y - b.x * a.y;
+            }
+            signed_area > 0.0
+        }
+    }
+}
+
+// The line or circle that a segment lands on once it has been offset
+enum OffsetCurve {
+    Line {
+        point: (f64, f64),
+        direction: (f64, f64),
+    },
+    Circle {
+        center: (f64, f64),
+        radius: f64,
+    },
+}
+
+impl OffsetCurve {
+    fn intersect(&self, other: &OffsetCurve) -> Vec<(f64, f64)> {
+        match (self, other) {
+            (
+                OffsetCurve::Line {
+                    point: (px, py),
+                    direction: (dx, dy),
+                },
+                OffsetCurve::Line {
+                    point: (qx, qy),
+                    direction: (ex, ey),
+                },
+            ) => {
+                let denominator = dx * ey - dy * ex;
+                if denominator.abs() < 1e-12 {
+                    return vec![];
+                }
+                let t = ((qx - px) * ey - (qy - py) * ex) / denominator;
+                vec![(px + dx * t, py + dy * t)]
+            }
+            (
+                OffsetCurve::Line {
+                    point: (px, py),
+                    direction: (dx, dy),
+                },
+                OffsetCurve::Circle {
+                    center: (cx, cy),
+                    radius,
+                },
+            )
+            | (
+                OffsetCurve::Circle {
+                    center: (cx, cy),
+                    radius,
+                },
+                OffsetCurve::Line {
+                    point: (px, py),
+                    direction: (dx, dy),
+                },
+            ) => {
+                // the direction is a unit vector, so this is a monic quadratic in t
+                let (fx, fy) = (px - cx, py - cy);
+                let b = fx * dx + fy * dy;
+                let c = fx * fx + fy * fy - radius * radius;
+                let discriminant = b * b - c;
+                if discriminant < 0.0 {
+                    return vec![];
+                }
+                [-b - discriminant.sqrt(), -b + discriminant.sqrt()]
+                    .iter()
+                    .map(|t| (px + dx * t, py + dy * t))
+                    .collect()
+            }
+            (
+                OffsetCurve::Circle {
+                    center: (ax, ay),
+                    radius: ra,
+                },
+                OffsetCurve::Circle {
+                    center: (bx, by),
+                    radius: rb,
+                },
+            ) => {
+                let d = (bx - ax).hypot(by - ay);
+                if d < 1e-12 || d > ra + rb || d < (ra - rb).abs() {
+                    return vec![];
+                }
+                let along = (ra * ra - rb * rb + d * d) / (2.0 * d);
+                let h = (ra * ra - along * along).max(0.0).sqrt();
+                let (ux, uy) = ((bx - ax) / d, (by - ay) / d);
+                let (mx, my) = (ax + ux * along, ay + uy * along);
+                vec![(mx - uy * h, my + ux * h), (mx + uy * h, my - ux * h)]
+            }
+        }
+    }
+}
+
+// Moves every segment of a counterclockwise ring `distance` to its left, which is into the
+// ring. Returns the new positions of the ring's end points (or of a circle's top point).
+pub fn offset_ring(
+    ring: &Ring,
+    points: &HashMap<u64, Point2>,
+    distance: f64,
+) -> HashMap<u64, Point2> {
+    let mut moved = HashMap::new();
+
+    let segments = match ring {
+        Ring::Circle(circle) => {
+            let center = &points[&circle.center];
+            let top = &points[&circle.top];
+            let radius = (top.x - center.x).hypot(top.y - center.y);
+            let scale = (radius - distance) / radius;
+            moved.insert(
+                circle.top,
+                Point2::new(
+                    center.x + (top.x - center.x) * scale,
+                    center.y + (top.y - center.y) * scale,
+                ),
+            );
+            return moved;
+        }
+        Ring::Segments(segments) => segments,
+    };
+
+    for (i, segment) in segments.iter().enumerate() {
+        let next = &segments[(i + 1) % segments.len()];
+        moved.insert(
+            segment.get_end(),
+            offset_corner(segment, next, points, distance),
+        );
+    }
+
+    moved
+}
+
+// Whether the ring still goes the same way round once it is offset by distance. A ring
+// offset so far that it shrinks down past nothing has its segments flipped around
+pub fn offset_keeps_ring(ring: &Ring, points: &HashMap<u64, Point2>, distance: f64) -> bool {
+    let moved = offset_ring(ring, points, distance);
+    match ring {
+        Ring::Circle(circle) => {
+            let center = &points[&circle.center];
+            let top = &points[&circle.top];
+            (top.x - center.x).hypot(top.y - center.y) - distance > 1e-9
+        }
+        Ring::Segments(segments) => segments.iter().all(|segment| {
+            let (start, end) = (segment.get_start(), segment.get_end());
+            let before = (
+                points[&end].x - points[&start].x,
+                points[&end].y - points[&start].y,
+            );
+            let after = (
+                moved[&end].x - moved[&start].x,
+                moved[&end].y - moved[&start].y,
+            );
+            before.0 * after.0 + before.1 * after.1 > 0.0
+        }),
+    }
+}
+
+// Moves every segment of an open chain `distance` to its left. Returns the new positions
+// of the chain's points, in order from its start to its end, or None if an arc would
+// shrink down past its own center
+pub fn offset_chain(
+    chain: &[Segment],
+    points: &HashMap<u64, Point2>,
+    distance: f64,
+) -> Option<Vec<Point2>> {
+    let (first, last) = match (chain.first(), chain.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Some(vec![]),
+    };
+    for segment in chain {
+        if let OffsetCurve::Circle { radius, .. } = offset_curve(segment, points, distance) {
+            if radius < 1e-9 {
+                return None;
+            }
+        }
+    }
+
+    // the loose ends just move straight out to the side
+    let shifted = |segment: &Segment, at: &Point2| {
+        let (nx, ny) = left_at(segment, at, points);
+        Point2::new(at.x + nx * distance, at.y + ny * distance)
+    };
+
+    let mut moved = vec![shifted(first, &points[&first.get_start()])];
+    for pair in chain.windows(2) {
+        moved.push(offset_corner(&pair[0], &pair[1], points, distance));
+    }
+    moved.push(shifted(last, &points[&last.get_end()]));
+    Some(moved)
+}
+
+// The unit normal pointing to the left of a segment as it passes through a point
+pub fn left_at(segment: &Segment, at: &Point2, points: &HashMap<u64, Point2>) -> (f64, f64) {
+    match segment {
+        Segment::Line(line) => {
+            let (start, end) = (&points[&line.start], &points[&line.end]);
+            let length = (end.x - start.x).hypot(end.y - start.y);
+            (-(end.y - start.y) / length, (end.x - start.x) / length)
+        }
+        Segment::Arc(arc) => {
+            let center = &points[&arc.center];
+            let radius = (at.x - center.x).hypot(at.y - center.y);
+            let (ox, oy) = ((at.x - center.x) / radius, (at.y - center.y) / radius);
+            // counterclockwise arcs bend around their center, which is on their left
+            if arc.clockwise {
+                (ox, oy)
+            } else {
+                (-ox, -oy)
+            }
+        }
+    }
+}
+
+fn offset_curve(segment: &Segment, points: &HashMap<u64, Point2>, distance: f64) -> OffsetCurve {
+    match segment {
+        Segment::Line(line) => {
+            let (start, end) = (&points[&line.start], &points[&line.end]);
+            let length = (end.x - start.x).hypot(end.y - start.y);
+            let (nx, ny) = left_at(segment, start, points);
+            OffsetCurve::Line {
+                point: (start.x + nx * distance, start.y + ny * distance),
+                direction: ((end.x - start.x) / length, (end.y - start.y) / length),
+            }
+        }
+        Segment::Arc(arc) => {
+            let (start, center) = (&points[&arc.start], &points[&arc.center]);
+            let radius = (start.x - center.x).hypot(start.y - center.y);
+            OffsetCurve::Circle {
+                center: (center.x, center.y),
+                radius: if arc.clockwise {
+                    radius + distance
+                } else {
+                    radius - distance
+                },
+            }
+        }
+    }
+}
+
+// Where the corner at the end of segment goes once it and the next segment have both
+// been moved `distance` to their left
+fn offset_corner(
+    segment: &Segment,
+    next: &Segment,
+    points: &HashMap<u64, Point2>,
+    distance: f64,
+) -> Point2 {
+    let corner = &points[&segment.get_end()];
+
+    // where the corner would go if both neighbors were tangent to each other
+    let (ax, ay) = left_at(segment, corner, points);
+    let (bx, by) = left_at(next, corner, points);
+    let (mx, my) = (ax + bx, ay + by);
+    let length = mx.hypot(my);
+    let guess = if length < 1e-12 {
+        (corner.x + ax * distance, corner.y + ay * distance)
+    } else {
+        (
+            corner.x + mx / length * distance,
+            corner.y + my / length * distance,
+        )
+    };
+
+    let new_corner = offset_curve(segment, points, distance)
+        .intersect(&offset_curve(next, points, distance))
+        .into_iter()
+        .min_by(|p, q| {
+            let dp = (p.0 - guess.0).hypot(p.1 - guess.1);
+            let dq = (q.0 - guess.0).hypot(q.1 - guess.1);
+            dp.total_cmp(&dq)
+        })
+        .unwrap_or(guess);
+
+    Point2::new(new_corner.0, new_corner.1)
+}
+
+// Unions two solids. Returns a single solid if they touch or overlap, or the separate
+// bodies if they are apart from each other. None means the union failed.
+pub fn fuse(
+    solid0: &TruckModelingSolid,
+    solid1: &TruckModelingSolid,
+) -> Option<Vec<TruckModelingSolid>> {
+    let (low0, high0) = solid_extent(solid0);
+    let (low1, high1) = solid_extent(solid1);
+    let gap = 1e-6;
+    if low0.x > high1.x + gap
+        || low1.x > high0.x + gap
+        || low0.y > high1.y + gap
+        || low1.y > high0.y + gap
+        || low0.z > high1.z + gap
+        || low1.z > high0.z + gap
+    {
+        return Some(vec![solid0.clone(), solid1.clone()]);
+    }
+
+    // truck's union has trouble with faces that press flat against each other, which is
+    // exactly what happens when a sketch on a face gets extruded. Those are stitched
+    // together by hand, anything else goes to truck
+    if let Some(stitched) = stitch(solid0, solid1) {
+        return Some(vec![stitched]);
+    }
+
+    // solids that only touch along an edge or at a corner come back from truck as one
+    // solid with a shell for each of them
+    [0.01, 0.05, 0.1]
+        .iter()
+        .find_map(|tolerance| truck_shapeops::or(solid0, solid1, *tolerance))
+        .map(|fused| split_shells(&fused))
+}
+
+// Joins two single shell solids along every pair of faces where they press flat against
+// each other. This only works when one face of each pair lies strictly inside the other,
+// so that the smaller one becomes a hole in the bigger one. A face can take holes from
+// several faces of the other solid, like a bridge sitting on two posts.
+fn stitch(solid0: &TruckModelingSolid, solid1: &TruckModelingSolid) -> Option<TruckModelingSolid> {
+    if solid0.boundaries().len() != 1 || solid1.boundaries().len() != 1 {
+        return None;
+    }
+    let boundaries = [&solid0.boundaries()[0], &solid1.boundaries()[0]];
+
+    let contacts = find_coplanar_face_pairs::<truck_modeling::Curve, Surface>(
+        boundaries[0],
+        boundaries[1],
+        true,
+    );
+    if contacts.is_empty() {
+        return None;
+    }
+
+    // faces are keyed by which solid they come from and their index in its shell
+    let mut holes: HashMap<(usize, usize), Vec<Wire>> = HashMap::new();
+    let mut swallowed: Vec<(usize, usize)> = vec![];
+    for (face_0_idx, face_1_idx) in contacts.iter() {
+        let extent_0 = face_extent(&boundaries[0][*face_0_idx]);
+        let extent_1 = face_extent(&boundaries[1][*face_1_idx]);
+
+        let (outer, inner) = if strictly_within(&extent_1, &extent_0) {
+            ((0, *face_0_idx), (1, *face_1_idx))
+        } else if strictly_within(&extent_0, &extent_1) {
+            ((1, *face_1_idx), (0, *face_0_idx))
+        } else {
+            return None;
+        };
+        let inner_face = &boundaries[inner.0][inner.1];
+        holes
+            .entry(outer)
+            .or_default()
+            .push(inner_face.boundaries().first()?.clone());
+        swallowed.push(inner);
+    }
+
+    // a face that holds one contact and sits inside another would need cutting in two
+    if swallowed.iter().any(|face| holes.contains_key(face)) {
+        return None;
+    }
+
+    let mut combined: Shell<TruckPoint3, truck_modeling::Curve, Surface> = Shell::new();
+    for (side, boundary) in boundaries.iter().enumerate() {
+        for (idx, face) in boundary.face_iter().enumerate() {
+            if swallowed.contains(&(side, idx)) {
+                continue;
+            }
+            let mut face = face.clone();
+            for hole in holes.get(&(side, idx)).into_iter().flatten() {
+                face.add_boundary(hole.clone());
+            }
+            combined.push(face);
+        }
+    }
+
+    // faces that end up side by side in the same plane are left as separate faces
+    TruckModelingSolid::try_new(vec![combined]).ok()
+}
+
+// The corners of the axis aligned box around every vertex of the solid
+fn solid_extent(solid: &TruckModelingSolid) -> (TruckPoint3, TruckPoint3) {
+    points_extent(solid.vertex_iter().map(|v| v.point()))
+}
+
+fn face_extent(face: &truck_modeling::Face) -> (TruckPoint3, TruckPoint3) {
+    points_extent(face.vertex_iter().map(|v| v.point()))
+}
+
+pub fn points_extent(points: impl Iterator<Item = TruckPoint3>) -> (TruckPoint3, TruckPoint3) {
+    let mut low = TruckPoint3::new(f64::MAX, f64::MAX, f64::MAX);
+    let mut high = TruckPoint3::new(f64::MIN, f64::MIN, f64::MIN);
+    for p in points {
+        low = TruckPoint3::new(low.x.min(p.x), low.y.min(p.y), low.z.min(p.z));
+        high = TruckPoint3::new(high.x.max(p.x), high.y.max(p.y), high.z.max(p.z));
+    }
+    (low, high)
+}
+
+// Whether the inner box fits inside the outer one without touching its sides. Directions
+// in which the outer box is flat (like the normal of a face) only need to line up.
+fn strictly_within(inner: &(TruckPoint3, TruckPoint3), outer: &(TruckPoint3, TruckPoint3)) -> bool {
+    let epsilon = 1e-6;
+    let (inner_low, inner_high) = inner;
+    let (outer_low, outer_high) = outer;
+    [
+        (inner_low.x, inner_high.x, outer_low.x, outer_high.x),
+        (inner_low.y, inner_high.y, outer_low.y, outer_high.y),
+        (inner_low.z, inner_high.z, outer_low.z, outer_high.z),
+    ]
+    .into_iter()
+    .all(|(inner_low, inner_high, outer_low, outer_high)| {
+        if outer_high - outer_low < epsilon {
+            (inner_low - outer_low).abs() < epsilon && (inner_high - outer_high).abs() < epsilon
+        } else {
+            inner_low > outer_low + epsilon && inner_high < outer_high - epsilon
+        }
+    })
+}
+
+// Cuts tool out of solid. truck can only intersect solids, so the cut is done by
+// intersecting with the tool turned inside out. Faces that nearly coincide can make the
+// intersection fail at one tolerance and succeed at another, so a few are tried.
+pub fn subtract(
+    solid: &TruckModelingSolid,
+    tool: &TruckModelingSolid,
+) -> Option<TruckModelingSolid> {
+    let mut inverted = tool.clone();
+    inverted.not();
+
+    [0.01, 0.05, 0.1]
+        .iter()
+        .find_map(|tolerance| truck_shapeops::and(solid, &inverted, *tolerance))
+}
+
+// The volume that two solids share, with the same tolerance retries as subtract
+pub fn intersect(
+    solid: &TruckModelingSolid,
+    tool: &TruckModelingSolid,
+) -> Option<TruckModelingSolid> {
+    [0.01, 0.05, 0.1]
+        .iter()
+        .find_map(|tolerance| truck_shapeops::and(solid, tool, *tolerance))
 }
 
 fn find_coplanar_face_pairs<C: ShapeOpsCurve<S>, S: ShapeOpsSurface>(
@@ -434,6 +1093,63 @@ mod tests {
         }
     }
 
+    fn cube(x: f64, y: f64, z: f64, size: f64) -> TruckModelingSolid {
+        let point = builder::vertex(TruckPoint3::new(x, y, z));
+        let line = builder::tsweep(&point, TruckVector3::new(size, 0.0, 0.0));
+        let square = builder::tsweep(&line, TruckVector3::new(0.0, size, 0.0));
+        builder::tsweep(&square, TruckVector3::new(0.0, 0.0, size))
+    }
+
+    #[test]
+    fn fuse_flush_cubes() {
+        // a small cube sitting in the middle of the top face of a big one
+        let fused = fuse(&cube(0.0, 0.0, 0.0, 1.0), &cube(0.4, 0.4, 1.0, 0.2)).unwrap();
+        assert_eq!(fused.len(), 1);
+        assert_eq!(fused[0].boundaries().len(), 1);
+    }
+
+    #[test]
+    fn fuse_overlapping_cubes() {
+        let fused = fuse(&cube(0.0, 0.0, 0.0, 1.0), &cube(0.5, 0.5, 0.5, 1.0)).unwrap();
+        assert_eq!(fused.len(), 1);
+        assert_eq!(fused[0].boundaries().len(), 1);
+    }
+
+    #[test]
+    fn fuse_bridge_on_two_contacts() {
+        // an upside down U standing on a slab, touching it with the bottoms of both legs
+        let legs = [
+            (0.2, 1.0),
+            (0.3, 1.0),
+            (0.3, 1.2),
+            (0.7, 1.2),
+            (0.7, 1.0),
+            (0.8, 1.0),
+            (0.8, 1.5),
+            (0.2, 1.5),
+        ];
+        let outline: Vec<TruckPoint3> = legs
+            .iter()
+            .map(|(x, z)| TruckPoint3::new(*x, 0.4, *z))
+            .collect();
+        let bridge = polygon_prism(&outline, TruckVector3::new(0.0, 0.2, 0.0)).unwrap();
+
+        let fused = fuse(&cube(0.0, 0.0, 0.0, 1.0), &bridge).unwrap();
+        assert_eq!(fused.len(), 1);
+        assert_eq!(fused[0].boundaries().len(), 1);
+
+        // the slab's top takes a hole for each leg, and the legs' bottoms are gone
+        let faces: Vec<_> = fused[0].boundaries()[0].face_iter().collect();
+        assert_eq!(faces.len(), 6 + 10 - 2);
+        assert!(faces.iter().any(|face| face.boundaries().len() == 3));
+    }
+
+    #[test]
+    fn fuse_disjoint_cubes() {
+        let fused = fuse(&cube(0.0, 0.0, 0.0, 1.0), &cube(2.0, 0.0, 0.0, 1.0)).unwrap();
+        assert_eq!(fused.len(), 2);
+    }
+
     #[test]
     fn step_export() {
         let p = create_test_project();
@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use truck_modeling::{builder, InnerSpace, Solid as TruckSolid, Wire};
+
+use crate::archetypes::EdgeDescription;
+use crate::error::CADmiumError;
+use crate::solid::EdgeCorner;
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct Fillet {
+    pub solid_id: String,
+    pub edges: Vec<EdgeDescription>,
+    pub radius: f64,
+}
+
+impl Fillet {
+    pub fn new(solid_id: String, edges: Vec<EdgeDescription>, radius: f64) -> Self {
+        Fillet {
+            solid_id,
+            edges,
+            radius,
+        }
+    }
+}
+
+// Builds the solid that has to be taken away from (convex edge) or added to (concave edge)
+// the body to round off the given edge. Its cross section is the sliver between the two
+// faces and the fillet arc, swept along the edge.
+pub fn fillet_tool(corner: &EdgeCorner, radius: f64) -> Result<TruckSolid, CADmiumError> {
+    let (normal_0, normal_1) = corner.normals;
+    let k = normal_0.dot(normal_1);
+    if k > 1.0 - 1e-6 || k < -1.0 + 1e-6 {
+        // the faces are either flush with each other or fold back into a knife edge
+        return Err(CADmiumError::UnsupportedEdge(corner.solid_name.clone()));
+    }
+
+    let along = (corner.end - corner.start).normalize();
+    let length = (corner.end - corner.start).magnitude();
+
+    // run the tool a little past both ends of the edge so that its caps are never
+    // coplanar with the faces at the ends of the edge
+    let start = corner.start - along * radius;
+
+    // the center of the fillet arc is one radius away from both faces: inside the
+    // material for a convex edge, outside of it for a concave one
+    let side = if corner.convex { -1.0 } else { 1.0 };
+    let center = start + (normal_0 + normal_1) * (side * radius / (1.0 + k));
+    let tangent_0 = center - normal_0 * (side * radius);
+    let tangent_1 = center - normal_1 * (side * radius);
+
+    let outward = (start - center).normalize();
+    let transit = center + outward * radius;
+
+    // push the tip of the sliver past the edge so the tool doesn't share any faces with the solid
+    let apex = start + outward * radius;
+
+    let v_apex = builder::vertex(apex);
+    let v_0 = builder::vertex(tangent_0);
+    let v_1 = builder::vertex(tangent_1);
+
+    // tsweep wants the face normal to point along the sweep direction
+    let wire: Wire = if (tangent_0 - apex).cross(tangent_1 - apex).dot(along) > 0.0 {
+        vec![
+            builder::line(&v_apex, &v_0),
+            builder::circle_arc(&v_0, &v_1, transit),
+            builder::line(&v_1, &v_apex),
+        ]
+    } else {
+        vec![
+            builder::line(&v_apex, &v_1),
+            builder::circle_arc(&v_1, &v_0, transit),
+            builder::line(&v_0, &v_apex),
+        ]
+    }
+    .into_iter()
+    .collect();
+
+    let face = builder::try_attach_plane(&[wire])
+        .map_err(|_| CADmiumError::GeometryConstructionFailed(corner.solid_name.clone()))?;
+
+    Ok(builder::tsweep(&face, along * (length + 2.0 * radius)))
+}
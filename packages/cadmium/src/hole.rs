@@ -0,0 +1,167 @@
+use std::f64::consts::PI;
+
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use truck_modeling::{builder, InnerSpace, Point3 as TruckPoint3, Rad, Solid as TruckSolid};
+use truck_modeling::{Vector3 as TruckVector3, Wire};
+
+use crate::archetypes::{Point3, Vector3};
+use crate::project::{RealPlane, RealSketch};
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct Hole {
+    pub sketch_id: String,
+    // every point is the center of one hole, drilled against the sketch plane's normal
+    pub point_ids: Vec<u64>,
+    pub diameter: f64,
+    pub depth: HoleDepth,
+    pub profile: HoleProfile,
+    // nominal thread size like "M3x0.5" or "1/4-20 UNC". Only recorded, not modeled
+    pub thread: Option<String>,
+    // the solids to drill into
+    pub solids: Vec<String>,
+}
+
+impl Hole {
+    pub fn new(
+        sketch_id: String,
+        point_ids: Vec<u64>,
+        diameter: f64,
+        depth: HoleDepth,
+        profile: HoleProfile,
+        thread: Option<String>,
+        solids: Vec<String>,
+    ) -> Self {
+        Hole {
+            sketch_id,
+            point_ids,
+            diameter,
+            depth,
+            profile,
+            thread,
+            solids,
+        }
+    }
+
+    // Where each of the holes starts and which way it goes, in 3D
+    pub fn callouts(&self, plane: &RealPlane, sketch: &RealSketch) -> Vec<HoleCallout> {
+        let direction = plane.plane.tertiary.times(-1.0);
+
+        self.point_ids
+            .iter()
+            .filter_map(|point_id| sketch.points.get(point_id))
+            .map(|center| HoleCallout {
+                center: Point3::new(center.x, center.y, center.z),
+                direction: direction.clone(),
+                radial: plane.plane.primary.clone(),
+                diameter: self.diameter,
+                depth: match self.depth {
+                    HoleDepth::Blind { depth } => Some(depth),
+                    HoleDepth::ThroughAll => None,
+                },
+                thread: self.thread.clone(),
+            })
+            .collect()
+    }
+}
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum HoleDepth {
+    Blind { depth: f64 },
+    ThroughAll,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum HoleProfile {
+    Simple,
+    Counterbore { diameter: f64, depth: f64 },
+    // angle is the included angle of the countersink cone in radians, usually PI / 2
+    Countersink { diameter: f64, angle: f64 },
+}
+
+// The design intent of a single hole, kept on the solid it was drilled into
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct HoleCallout {
+    pub center: Point3,
+    pub direction: Vector3,
+    pub radial: Vector3,
+    pub diameter: f64,
+    // None means through all
+    pub depth: Option<f64>,
+    pub thread: Option<String>,
+}
+
+// Builds the solid that gets removed to make one hole, by spinning its half cross
+// section around the hole's axis. None means the counterbore or countersink doesn't fit:
+// it has to be wider than the hole and end before the bottom of it
+pub fn hole_tool(callout: &HoleCallout, profile: &HoleProfile, depth: f64) -> Option<TruckSolid> {
+    let center = TruckPoint3::new(callout.center.x, callout.center.y, callout.center.z);
+    let direction = TruckVector3::new(
+        callout.direction.x,
+        callout.direction.y,
+        callout.direction.z,
+    )
+    .normalize();
+    let radial = TruckVector3::new(callout.radial.x, callout.radial.y, callout.radial.z);
+    let radial = (radial - direction * radial.dot(direction)).normalize();
+
+    let radius = callout.diameter / 2.0;
+
+    // the tool starts a bit above the surface so that its top isn't coplanar with it
+    let standoff = callout.diameter * 0.1;
+
+    // (radius, depth) pairs, walking down the outside of the hole
+    let mut outline: Vec<(f64, f64)> = vec![(0.0, -standoff)];
+    match profile {
+        HoleProfile::Simple => {
+            outline.push((radius, -standoff));
+        }
+        HoleProfile::Counterbore {
+            diameter: bore_diameter,
+            depth: bore_depth,
+        } => {
+            if *bore_diameter <= callout.diameter || *bore_depth <= 0.0 || *bore_depth >= depth {
+                return None;
+            }
+            outline.push((bore_diameter / 2.0, -standoff));
+            outline.push((bore_diameter / 2.0, *bore_depth));
+            outline.push((radius, *bore_depth));
+        }
+        HoleProfile::Countersink {
+            diameter: sink_diameter,
+            angle,
+        } => {
+            if *sink_diameter <= callout.diameter || *angle <= 0.0 || *angle >= PI {
+                return None;
+            }
+            let slope = (angle / 2.0).tan();
+            let sink_depth = (sink_diameter / 2.0 - radius) / slope;
+            if sink_depth >= depth {
+                return None;
+            }
+            outline.push((sink_diameter / 2.0 + standoff * slope, -standoff));
+            outline.push((radius, sink_depth));
+        }
+    }
+    outline.push((radius, depth));
+    outline.push((0.0, depth));
+
+    let vertices: Vec<_> = outline
+        .iter()
+        .map(|(r, h)| builder::vertex(center + radial * *r + direction * *h))
+        .collect();
+    let wire: Wire = vertices
+        .windows(2)
+        .map(|pair| builder::line(&pair[0], &pair[1]))
+        .collect();
+
+    // the wire runs from the top of the axis to the bottom, so the axis has to point
+    // back up out of the hole for the shell to face outward
+    let shell = builder::cone(&wire, -direction, Rad(2.0 * PI));
+    Some(TruckSolid::new(vec![shell]))
+}
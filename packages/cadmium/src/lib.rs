@@ -3,14 +3,31 @@ use wasm_bindgen::prelude::*;
 extern crate console_error_panic_hook;
 
 pub mod archetypes;
+pub mod axis;
+pub mod boolean;
+pub mod chamfer;
+pub mod draft;
 pub mod error;
 pub mod extrusion;
+pub mod fillet;
+pub mod hole;
+pub mod loft;
 pub mod message;
+pub mod mirror;
+pub mod offset_face;
+pub mod pattern;
+pub mod point;
 pub mod project;
 pub mod realization;
+pub mod revolve;
+pub mod rib;
+pub mod shell;
 pub mod solid;
 pub mod sketch;
+pub mod split;
 pub mod step;
+pub mod sweep;
+pub mod transform;
 pub mod workbench;
 
 #[wasm_bindgen]
@@ -0,0 +1,390 @@
+use std::f64::consts::PI;
+
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use truck_modeling::{builder, InnerSpace, Matrix3, Wire};
+use truck_modeling::{Point3 as TruckPoint3, Solid as TruckSolid, Vector3 as TruckVector3};
+use truck_polymesh::Rad;
+
+use crate::archetypes::Point3;
+use crate::error::CADmiumError;
+use crate::extrusion::ExtrusionMode;
+use crate::project::{RealPlane, RealSketch};
+use crate::sketch::{Ring, Segment};
+use crate::sweep::arc_angle;
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct LoftProfile {
+    pub sketch_id: String,
+    pub face_id: u64,
+    // the sketch point that lines up with the start of every other profile. When left
+    // out, the corner that best lines up with the previous profile's start is used
+    pub start_point: Option<u64>,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct Loft {
+    // in the order the loft passes through them
+    pub profiles: Vec<LoftProfile>,
+    pub mode: ExtrusionMode,
+}
+
+impl Loft {
+    pub fn new(profiles: Vec<LoftProfile>, mode: ExtrusionMode) -> Self {
+        Loft { profiles, mode }
+    }
+}
+
+// One edge of a profile's outline in 3D
+#[derive(Debug, Clone)]
+pub enum OutlineEdge {
+    Line {
+        start: TruckPoint3,
+        end: TruckPoint3,
+    },
+    // a turn about the axis through center, following the right hand rule
+    Arc {
+        start: TruckPoint3,
+        center: TruckPoint3,
+        axis: TruckVector3,
+        angle: f64,
+    },
+}
+
+impl OutlineEdge {
+    fn point_at(&self, t: f64) -> TruckPoint3 {
+        match self {
+            OutlineEdge::Line { start, end } => start + (end - start) * t,
+            OutlineEdge::Arc {
+                start,
+                center,
+                axis,
+                angle,
+            } => center + Matrix3::from_axis_angle(*axis, Rad(angle * t)) * (start - center),
+        }
+    }
+
+    fn length(&self) -> f64 {
+        match self {
+            OutlineEdge::Line { start, end } => (end - start).magnitude(),
+            OutlineEdge::Arc {
+                start,
+                center,
+                angle,
+                ..
+            } => (start - center).magnitude() * angle,
+        }
+    }
+
+    fn reversed(&self) -> Self {
+        match self {
+            OutlineEdge::Line { start, end } => OutlineEdge::Line {
+                start: *end,
+                end: *start,
+            },
+            OutlineEdge::Arc {
+                center,
+                axis,
+                angle,
+                ..
+            } => OutlineEdge::Arc {
+                start: self.point_at(1.0),
+                center: *center,
+                axis: -*axis,
+                angle: *angle,
+            },
+        }
+    }
+
+    // Cuts the edge into equal parts
+    fn split(&self, parts: usize) -> Vec<Self> {
+        (0..parts)
+            .map(|i| {
+                let from = self.point_at(i as f64 / parts as f64);
+                match self {
+                    OutlineEdge::Line { .. } => OutlineEdge::Line {
+                        start: from,
+                        end: self.point_at((i + 1) as f64 / parts as f64),
+                    },
+                    OutlineEdge::Arc {
+                        center,
+                        axis,
+                        angle,
+                        ..
+                    } => OutlineEdge::Arc {
+                        start: from,
+                        center: *center,
+                        axis: *axis,
+                        angle: angle / parts as f64,
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+// The exterior of a profile's face as a closed loop of edges, starting at the profile's
+// start point if it has one
+pub fn outline(
+    plane: &RealPlane,
+    sketch: &RealSketch,
+    profile: &LoftProfile,
+) -> Result<Vec<OutlineEdge>, CADmiumError> {
+    let face = sketch
+        .faces
+        .get(profile.face_id as usize)
+        .ok_or(CADmiumError::FaceNotFound(profile.sketch_id.clone()))?;
+    // only the outside of a profile can be lofted
+    if !face.holes.is_empty() {
+        return Err(CADmiumError::UnsupportedShape(profile.sketch_id.clone()));
+    }
+
+    let point = |id: &u64| -> Result<&Point3, CADmiumError> {
+        sketch
+            .points
+            .get(id)
+            .ok_or(CADmiumError::PointNotFound(id.to_string()))
+    };
+    let truck_point = |p: &Point3| TruckPoint3::new(p.x, p.y, p.z);
+    let normal = TruckVector3::new(
+        plane.plane.tertiary.x,
+        plane.plane.tertiary.y,
+        plane.plane.tertiary.z,
+    )
+    .normalize();
+
+    let mut edges = match &face.exterior {
+        Ring::Circle(circle) => vec![OutlineEdge::Arc {
+            start: truck_point(point(&circle.top)?),
+            center: truck_point(point(&circle.center)?),
+            axis: normal,
+            angle: 2.0 * PI,
+        }],
+        Ring::Segments(segments) => {
+            let mut edges = vec![];
+            for segment in segments {
+                edges.push(match segment {
+                    Segment::Line(line) => OutlineEdge::Line {
+                        start: truck_point(point(&line.start)?),
+                        end: truck_point(point(&line.end)?),
+                    },
+                    Segment::Arc(arc) => {
+                        let (start, end, center) =
+                            (point(&arc.start)?, point(&arc.end)?, point(&arc.center)?);
+                        OutlineEdge::Arc {
+                            start: truck_point(start),
+                            center: truck_point(center),
+                            axis: if arc.clockwise { -normal } else { normal },
+                            angle: arc_angle(plane, start, end, center, arc.clockwise),
+                        }
+                    }
+                });
+            }
+            edges
+        }
+    };
+
+    if let Some(start_id) = profile.start_point {
+        let start = truck_point(point(&start_id)?);
+        edges = start_at(&edges, start).ok_or(CADmiumError::PointNotFound(start_id.to_string()))?;
+    }
+
+    Ok(edges)
+}
+
+// Gets a set of outlines ready to be lofted: every outline winds the same way about the
+// direction the loft travels, the outlines without a chosen start begin at the corner
+// that best lines up with the start of the outline before them, and they are all split
+// into the same number of edges
+pub fn line_up(
+    mut outlines: Vec<Vec<OutlineEdge>>,
+    fixed_starts: &[bool],
+) -> Vec<Vec<OutlineEdge>> {
+    let centroids: Vec<TruckPoint3> = outlines.iter().map(|outline| centroid(outline)).collect();
+    let travel = match (centroids.first(), centroids.last()) {
+        (Some(first), Some(last)) => last - first,
+        _ => return outlines,
+    };
+
+    for outline in outlines.iter_mut() {
+        if area_vector(outline).dot(travel) < 0.0 {
+            *outline = outline.iter().rev().map(|edge| edge.reversed()).collect();
+        }
+    }
+
+    for i in 1..outlines.len() {
+        if fixed_starts.get(i) == Some(&true) {
+            continue;
+        }
+        let toward = outlines[i - 1][0].point_at(0.0) - centroids[i - 1];
+        outlines[i] = start_toward(&outlines[i], centroids[i], toward);
+    }
+
+    // a single edge can't close a loop, so even circles get split in two
+    let count = outlines
+        .iter()
+        .map(|outline| outline.len())
+        .max()
+        .unwrap_or(0)
+        .max(2);
+    outlines
+        .iter()
+        .map(|outline| subdivide(outline, count))
+        .collect()
+}
+
+// Joins the outlines into a solid, capped by flat faces at both ends. The outlines have to
+// be lined up already
+pub fn loft_solid(outlines: &[Vec<OutlineEdge>]) -> Option<TruckSolid> {
+    let wires: Vec<Wire> = outlines.iter().map(|outline| to_wire(outline)).collect();
+
+    let mut shell = vec![];
+    for pair in wires.windows(2) {
+        shell.extend(
+            builder::wire_homotopy(&pair[0], &pair[1])
+                .face_iter()
+                .cloned(),
+        );
+    }
+    shell.push(
+        builder::try_attach_plane(&[wires.first()?.clone()])
+            .ok()?
+            .inverse(),
+    );
+    shell.push(builder::try_attach_plane(&[wires.last()?.clone()]).ok()?);
+
+    TruckSolid::try_new(vec![shell.into_iter().collect()]).ok()
+}
+
+fn samples(outline: &[OutlineEdge]) -> Vec<TruckPoint3> {
+    outline
+        .iter()
+        .flat_map(|edge| (0..8).map(move |i| edge.point_at(i as f64 / 8.0)))
+        .collect()
+}
+
+fn centroid(outline: &[OutlineEdge]) -> TruckPoint3 {
+    let points = samples(outline);
+    let sum = points
+        .iter()
+        .fold(TruckVector3::new(0.0, 0.0, 0.0), |sum, p| {
+            sum + (p - TruckPoint3::new(0.0, 0.0, 0.0))
+        });
+    TruckPoint3::new(0.0, 0.0, 0.0) + sum / points.len().max(1) as f64
+}
+
+// Points along the normal of the outline, for an outline that winds counterclockwise
+fn area_vector(outline: &[OutlineEdge]) -> TruckVector3 {
+    let points = samples(outline);
+    let center = centroid(outline);
+    (0..points.len()).fold(TruckVector3::new(0.0, 0.0, 0.0), |sum, i| {
+        let next = &points[(i + 1) % points.len()];
+        sum + (points[i] - center).cross(next - center)
+    })
+}
+
+fn start_at(outline: &[OutlineEdge], start: TruckPoint3) -> Option<Vec<OutlineEdge>> {
+    // a full circle can start anywhere on it
+    if let [OutlineEdge::Arc {
+        center,
+        axis,
+        angle,
+        ..
+    }] = outline
+    {
+        if *angle >= 2.0 * PI - 1e-9 {
+            let radius = outline[0].length() / angle;
+            let out = (start - center) - axis * axis.dot(start - center);
+            if out.magnitude() < 1e-9 {
+                return None;
+            }
+            return Some(vec![OutlineEdge::Arc {
+                start: center + out.normalize() * radius,
+                center: *center,
+                axis: *axis,
+                angle: *angle,
+            }]);
+        }
+    }
+
+    let index = outline
+        .iter()
+        .position(|edge| (edge.point_at(0.0) - start).magnitude() < 1e-6)?;
+    Some([&outline[index..], &outline[..index]].concat())
+}
+
+fn start_toward(
+    outline: &[OutlineEdge],
+    center: TruckPoint3,
+    toward: TruckVector3,
+) -> Vec<OutlineEdge> {
+    if toward.magnitude() < 1e-9 {
+        return outline.to_vec();
+    }
+    let toward = toward.normalize();
+
+    // circles start right where they are pointed at, anything else at its best corner
+    let start = match outline {
+        [OutlineEdge::Arc { .. }] => Some(center + toward),
+        _ => outline
+            .iter()
+            .map(|edge| edge.point_at(0.0))
+            .max_by(|a, b| {
+                let lean = |p: &TruckPoint3| {
+                    let out = p - center;
+                    if out.magnitude() < 1e-9 {
+                        -1.0
+                    } else {
+                        out.normalize().dot(toward)
+                    }
+                };
+                lean(a).total_cmp(&lean(b))
+            }),
+    };
+
+    start
+        .and_then(|start| start_at(outline, start))
+        .unwrap_or_else(|| outline.to_vec())
+}
+
+// Splits the longest edges until the outline has count edges
+fn subdivide(outline: &[OutlineEdge], count: usize) -> Vec<OutlineEdge> {
+    let mut parts = vec![1; outline.len()];
+    while !outline.is_empty() && parts.iter().sum::<usize>() < count {
+        let longest = (0..outline.len())
+            .max_by(|&a, &b| {
+                let a = outline[a].length() / parts[a] as f64;
+                let b = outline[b].length() / parts[b] as f64;
+                a.total_cmp(&b)
+            })
+            .unwrap();
+        parts[longest] += 1;
+    }
+
+    outline
+        .iter()
+        .zip(parts)
+        .flat_map(|(edge, parts)| edge.split(parts))
+        .collect()
+}
+
+fn to_wire(outline: &[OutlineEdge]) -> Wire {
+    let vertices: Vec<_> = outline
+        .iter()
+        .map(|edge| builder::vertex(edge.point_at(0.0)))
+        .collect();
+    (0..outline.len())
+        .map(|i| {
+            let (start, end) = (&vertices[i], &vertices[(i + 1) % vertices.len()]);
+            match outline[i] {
+                OutlineEdge::Line { .. } => builder::line(start, end),
+                OutlineEdge::Arc { .. } => {
+                    builder::circle_arc(start, end, outline[i].point_at(0.5))
+                }
+            }
+        })
+        .collect()
+}
@@ -55,7 +55,7 @@ fn main() {
     // let combined_big = or(&cube_a, &cube_b, 0.01).unwrap();
 
     // let combined = or(&cube_a, &cube_b, 0.01).unwrap();
-    let combined = fuse(&cube_a, &cube_b).unwrap();
+    let combined = fuse(&cube_a, &cube_b).unwrap().remove(0);
 
     println!(
         "combined_cube_or has {:?} shell boundaries",
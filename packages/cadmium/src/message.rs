@@ -3,11 +3,31 @@ use itertools::Itertools as _;
 use serde::{Deserialize, Serialize};
 use tsify::Tsify;
 
-use crate::archetypes::PlaneDescription;
+use crate::archetypes::{EdgeDescription, Point3, Vector3};
+use crate::axis::Axis;
+use crate::boolean::{Boolean, BooleanOperation};
+use crate::chamfer::{Chamfer, ChamferMode};
+use crate::draft::Draft;
 use crate::error::CADmiumError;
-use crate::extrusion::{Direction, Extrusion, ExtrusionMode};
+use crate::extrusion::{Direction, Extrusion, ExtrusionEnd, ExtrusionMode, ThinWall, WallSide};
+use crate::fillet::Fillet;
+use crate::hole::{Hole, HoleDepth, HoleProfile};
+use crate::loft::{Loft, LoftProfile};
+use crate::mirror::{Mirror, MirrorPlane};
+use crate::offset_face::OffsetFace;
+use crate::pattern::{
+    CircularPattern, LinearPattern, PatternAxis, PatternDirection, PatternSource,
+};
+use crate::point::PointDescription;
 use crate::project::Project;
+use crate::realization::PlaneDescription;
+use crate::revolve::{Revolve, RevolveAxis};
+use crate::rib::Rib;
+use crate::shell::Shell;
+use crate::split::{Split, SplitKeep, SplitTool};
 use crate::step::StepData;
+use crate::sweep::{PathSegment, Sweep, SweepOrientation};
+use crate::transform::{Motion, Transform};
 
 #[derive(Tsify, Debug, Serialize, Deserialize)]
 #[tsify(into_wasm_abi, from_wasm_abi)]
@@ -110,6 +130,11 @@ pub enum Message {
         sketch_name: String,
         plane_id: String,
     },
+    NewSketchOnDerivedPlane {
+        workbench_id: u64,
+        sketch_name: String,
+        plane_description: PlaneDescription,
+    },
     SetSketchPlane {
         workbench_id: u64,
         sketch_id: String,
@@ -143,6 +168,170 @@ pub enum Message {
         extrusion_name: String,
         length: f64,
     },
+    UpdateExtrusionDraftAngle {
+        workbench_id: u64,
+        extrusion_name: String,
+        draft_angle: f64,
+    },
+    UpdateExtrusionEnd {
+        workbench_id: u64,
+        extrusion_name: String,
+        end: ExtrusionEnd,
+    },
+    UpdateExtrusionSecondLength {
+        workbench_id: u64,
+        extrusion_name: String,
+        second_length: f64,
+    },
+    UpdateExtrusionThinWall {
+        workbench_id: u64,
+        extrusion_name: String,
+        thin: Option<ThinWall>,
+    },
+    NewRevolve {
+        workbench_id: u64,
+        revolve_name: String,
+        sketch_id: String,
+        face_ids: Vec<u64>,
+        axis: RevolveAxis,
+        angle: f64,
+        mode: ExtrusionMode,
+    },
+    NewFillet {
+        workbench_id: u64,
+        fillet_name: String,
+        solid_id: String,
+        edges: Vec<EdgeDescription>,
+        radius: f64,
+    },
+    NewChamfer {
+        workbench_id: u64,
+        chamfer_name: String,
+        solid_id: String,
+        edges: Vec<EdgeDescription>,
+        mode: ChamferMode,
+    },
+    NewHole {
+        workbench_id: u64,
+        hole_name: String,
+        sketch_id: String,
+        point_ids: Vec<u64>,
+        diameter: f64,
+        depth: HoleDepth,
+        profile: HoleProfile,
+        thread: Option<String>,
+        solids: Vec<String>,
+    },
+    NewDraft {
+        workbench_id: u64,
+        draft_name: String,
+        solid_id: String,
+        face_normals: Vec<Vector3>,
+        neutral_plane_id: String,
+        angle: f64,
+    },
+    NewShell {
+        workbench_id: u64,
+        shell_name: String,
+        solid_id: String,
+        thickness: f64,
+        open_faces: Vec<Vector3>,
+    },
+    NewLinearPattern {
+        workbench_id: u64,
+        pattern_name: String,
+        source: PatternSource,
+        first: PatternDirection,
+        second: Option<PatternDirection>,
+    },
+    NewCircularPattern {
+        workbench_id: u64,
+        pattern_name: String,
+        source: PatternSource,
+        axis: PatternAxis,
+        count: u64,
+        angle: f64,
+        equal_spacing: bool,
+    },
+    NewMirror {
+        workbench_id: u64,
+        mirror_name: String,
+        sources: Vec<PatternSource>,
+        plane: MirrorPlane,
+        merge: bool,
+    },
+    NewSweep {
+        workbench_id: u64,
+        sweep_name: String,
+        profile_sketch_id: String,
+        face_ids: Vec<u64>,
+        path_sketch_id: String,
+        path: Vec<PathSegment>,
+        orientation: SweepOrientation,
+        mode: ExtrusionMode,
+    },
+    NewLoft {
+        workbench_id: u64,
+        loft_name: String,
+        profiles: Vec<LoftProfile>,
+        mode: ExtrusionMode,
+    },
+    NewRib {
+        workbench_id: u64,
+        rib_name: String,
+        sketch_id: String,
+        segment: PathSegment,
+        solid_id: String,
+        thickness: f64,
+        side: WallSide,
+        direction: Direction,
+    },
+    NewSplit {
+        workbench_id: u64,
+        split_name: String,
+        solid_id: String,
+        tool: SplitTool,
+        keep: SplitKeep,
+    },
+    NewBoolean {
+        workbench_id: u64,
+        boolean_name: String,
+        target_id: String,
+        tool_ids: Vec<String>,
+        operation: BooleanOperation,
+        keep_tools: bool,
+    },
+    NewTransform {
+        workbench_id: u64,
+        transform_name: String,
+        solid_ids: Vec<String>,
+        motion: Motion,
+        copy: bool,
+    },
+    NewOffsetFace {
+        workbench_id: u64,
+        offset_face_name: String,
+        solid_id: String,
+        face_normals: Vec<Vector3>,
+        #[serde(default)]
+        near: Vec<Option<Point3>>,
+        distance: f64,
+    },
+    NewAxis {
+        workbench_id: u64,
+        axis_name: String,
+        axis: Axis,
+    },
+    NewDerivedPoint {
+        workbench_id: u64,
+        point_name: String,
+        point: PointDescription,
+    },
+    NewDerivedPlane {
+        workbench_id: u64,
+        plane_name: String,
+        plane: PlaneDescription,
+    },
 }
 
 impl Message {
@@ -331,6 +520,17 @@ impl Message {
                 let new_sketch_id = workbench.add_sketch_to_plane(&sketch_name, &plane_id);
                 Ok(format!("\"sketch_id\": \"{}\"", new_sketch_id))
             }
+            Message::NewSketchOnDerivedPlane {
+                workbench_id,
+                sketch_name,
+                plane_description,
+            } => {
+                let workbench = project.get_workbench_by_id_mut(*workbench_id)?;
+
+                let new_sketch_id = workbench
+                    .add_sketch_to_derived_plane(&sketch_name, plane_description.to_owned());
+                Ok(format!("\"sketch_id\": \"{}\"", new_sketch_id))
+            }
             Message::SetSketchPlane {
                 workbench_id,
                 sketch_id,
@@ -398,17 +598,20 @@ impl Message {
                 direction,
             } => {
                 let workbench = project.get_workbench_by_id_mut(*workbench_id)?;
-                let extrusion = Extrusion::new(
-                    sketch_id.to_owned(),
-                    face_ids.to_owned(),
-                    *length,
-                    *offset,
-                    direction.to_owned(),
-                    ExtrusionMode::New,
-                );
-                let as_step_data = StepData::Extrusion { extrusion };
-                workbench.update_step_data(extrusion_id, as_step_data);
-                Ok(format!("\"id\": \"{}\"", extrusion_id))
+                let step = workbench.get_step_by_id_mut(extrusion_id)?;
+
+                // only the fields in the message change, so a draft, end condition,
+                // second side or thin wall set up by other messages is kept
+                if let StepData::Extrusion { extrusion } = &mut step.data {
+                    extrusion.sketch_id = sketch_id.to_owned();
+                    extrusion.face_ids = face_ids.to_owned();
+                    extrusion.length = *length;
+                    extrusion.offset = *offset;
+                    extrusion.direction = direction.to_owned();
+                    return Ok(format!("\"id\": \"{}\"", extrusion_id));
+                }
+
+                Err(CADmiumError::IncorrectStepDataType("Extrusion".to_owned()).into())
             }
             Message::UpdateExtrusionLength {
                 workbench_id,
@@ -425,6 +628,351 @@ impl Message {
 
                 Err(CADmiumError::IncorrectStepDataType("Extrusion".to_owned()).into())
             }
+            Message::UpdateExtrusionDraftAngle {
+                workbench_id,
+                extrusion_name,
+                draft_angle,
+            } => {
+                let workbench = project.get_workbench_by_id_mut(*workbench_id)?;
+                let step = workbench.get_step_mut(&extrusion_name)?;
+
+                if let StepData::Extrusion { extrusion } = &mut step.data {
+                    extrusion.draft_angle = *draft_angle;
+                    return Ok(format!("\"draft_angle\": {}", draft_angle));
+                }
+
+                Err(CADmiumError::IncorrectStepDataType("Extrusion".to_owned()).into())
+            }
+            Message::UpdateExtrusionEnd {
+                workbench_id,
+                extrusion_name,
+                end,
+            } => {
+                let workbench = project.get_workbench_by_id_mut(*workbench_id)?;
+                let step = workbench.get_step_mut(&extrusion_name)?;
+
+                if let StepData::Extrusion { extrusion } = &mut step.data {
+                    extrusion.end = end.to_owned();
+                    return Ok(format!("\"end\": {}", serde_json::to_string(end)?));
+                }
+
+                Err(CADmiumError::IncorrectStepDataType("Extrusion".to_owned()).into())
+            }
+            Message::UpdateExtrusionSecondLength {
+                workbench_id,
+                extrusion_name,
+                second_length,
+            } => {
+                let workbench = project.get_workbench_by_id_mut(*workbench_id)?;
+                let step = workbench.get_step_mut(&extrusion_name)?;
+
+                if let StepData::Extrusion { extrusion } = &mut step.data {
+                    extrusion.second_length = *second_length;
+                    return Ok(format!("\"second_length\": {}", second_length));
+                }
+
+                Err(CADmiumError::IncorrectStepDataType("Extrusion".to_owned()).into())
+            }
+            Message::UpdateExtrusionThinWall {
+                workbench_id,
+                extrusion_name,
+                thin,
+            } => {
+                let workbench = project.get_workbench_by_id_mut(*workbench_id)?;
+                let step = workbench.get_step_mut(&extrusion_name)?;
+
+                if let StepData::Extrusion { extrusion } = &mut step.data {
+                    extrusion.thin = thin.to_owned();
+                    return Ok(format!("\"thin\": {}", serde_json::to_string(thin)?));
+                }
+
+                Err(CADmiumError::IncorrectStepDataType("Extrusion".to_owned()).into())
+            }
+            Message::NewRevolve {
+                workbench_id,
+                revolve_name,
+                sketch_id,
+                face_ids,
+                axis,
+                angle,
+                mode,
+            } => {
+                let workbench = project.get_workbench_by_id_mut(*workbench_id)?;
+                let revolve = Revolve::new(
+                    sketch_id.to_owned(),
+                    face_ids.to_owned(),
+                    axis.to_owned(),
+                    *angle,
+                    mode.to_owned(),
+                );
+                let revolve_id = workbench.add_revolve(revolve_name, revolve);
+                Ok(format!("\"id\": \"{}\"", revolve_id))
+            }
+            Message::NewFillet {
+                workbench_id,
+                fillet_name,
+                solid_id,
+                edges,
+                radius,
+            } => {
+                let workbench = project.get_workbench_by_id_mut(*workbench_id)?;
+                let fillet = Fillet::new(solid_id.to_owned(), edges.to_owned(), *radius);
+                let fillet_id = workbench.add_fillet(fillet_name, fillet);
+                Ok(format!("\"id\": \"{}\"", fillet_id))
+            }
+            Message::NewChamfer {
+                workbench_id,
+                chamfer_name,
+                solid_id,
+                edges,
+                mode,
+            } => {
+                let workbench = project.get_workbench_by_id_mut(*workbench_id)?;
+                let chamfer = Chamfer::new(solid_id.to_owned(), edges.to_owned(), mode.to_owned());
+                let chamfer_id = workbench.add_chamfer(chamfer_name, chamfer);
+                Ok(format!("\"id\": \"{}\"", chamfer_id))
+            }
+            Message::NewHole {
+                workbench_id,
+                hole_name,
+                sketch_id,
+                point_ids,
+                diameter,
+                depth,
+                profile,
+                thread,
+                solids,
+            } => {
+                let workbench = project.get_workbench_by_id_mut(*workbench_id)?;
+                let hole = Hole::new(
+                    sketch_id.to_owned(),
+                    point_ids.to_owned(),
+                    *diameter,
+                    depth.to_owned(),
+                    profile.to_owned(),
+                    thread.to_owned(),
+                    solids.to_owned(),
+                );
+                let hole_id = workbench.add_hole(hole_name, hole);
+                Ok(format!("\"id\": \"{}\"", hole_id))
+            }
+            Message::NewDraft {
+                workbench_id,
+                draft_name,
+                solid_id,
+                face_normals,
+                neutral_plane_id,
+                angle,
+            } => {
+                let workbench = project.get_workbench_by_id_mut(*workbench_id)?;
+                let draft = Draft::new(
+                    solid_id.to_owned(),
+                    face_normals.to_owned(),
+                    neutral_plane_id.to_owned(),
+                    *angle,
+                );
+                let draft_id = workbench.add_draft(draft_name, draft);
+                Ok(format!("\"id\": \"{}\"", draft_id))
+            }
+            Message::NewShell {
+                workbench_id,
+                shell_name,
+                solid_id,
+                thickness,
+                open_faces,
+            } => {
+                let workbench = project.get_workbench_by_id_mut(*workbench_id)?;
+                let shell = Shell::new(solid_id.to_owned(), *thickness, open_faces.to_owned());
+                let shell_id = workbench.add_shell(shell_name, shell);
+                Ok(format!("\"id\": \"{}\"", shell_id))
+            }
+            Message::NewLinearPattern {
+                workbench_id,
+                pattern_name,
+                source,
+                first,
+                second,
+            } => {
+                let workbench = project.get_workbench_by_id_mut(*workbench_id)?;
+                let pattern =
+                    LinearPattern::new(source.to_owned(), first.to_owned(), second.to_owned());
+                let pattern_id = workbench.add_linear_pattern(pattern_name, pattern);
+                Ok(format!("\"id\": \"{}\"", pattern_id))
+            }
+            Message::NewCircularPattern {
+                workbench_id,
+                pattern_name,
+                source,
+                axis,
+                count,
+                angle,
+                equal_spacing,
+            } => {
+                let workbench = project.get_workbench_by_id_mut(*workbench_id)?;
+                let pattern = CircularPattern::new(
+                    source.to_owned(),
+                    axis.to_owned(),
+                    *count,
+                    *angle,
+                    *equal_spacing,
+                );
+                let pattern_id = workbench.add_circular_pattern(pattern_name, pattern);
+                Ok(format!("\"id\": \"{}\"", pattern_id))
+            }
+            Message::NewMirror {
+                workbench_id,
+                mirror_name,
+                sources,
+                plane,
+                merge,
+            } => {
+                let workbench = project.get_workbench_by_id_mut(*workbench_id)?;
+                let mirror = Mirror::new(sources.to_owned(), plane.to_owned(), *merge);
+                let mirror_id = workbench.add_mirror(mirror_name, mirror);
+                Ok(format!("\"id\": \"{}\"", mirror_id))
+            }
+            Message::NewSweep {
+                workbench_id,
+                sweep_name,
+                profile_sketch_id,
+                face_ids,
+                path_sketch_id,
+                path,
+                orientation,
+                mode,
+            } => {
+                let workbench = project.get_workbench_by_id_mut(*workbench_id)?;
+                let sweep = Sweep::new(
+                    profile_sketch_id.to_owned(),
+                    face_ids.to_owned(),
+                    path_sketch_id.to_owned(),
+                    path.to_owned(),
+                    orientation.to_owned(),
+                    mode.to_owned(),
+                );
+                let sweep_id = workbench.add_sweep(sweep_name, sweep);
+                Ok(format!("\"id\": \"{}\"", sweep_id))
+            }
+            Message::NewLoft {
+                workbench_id,
+                loft_name,
+                profiles,
+                mode,
+            } => {
+                let workbench = project.get_workbench_by_id_mut(*workbench_id)?;
+                let loft = Loft::new(profiles.to_owned(), mode.to_owned());
+                let loft_id = workbench.add_loft(loft_name, loft);
+                Ok(format!("\"id\": \"{}\"", loft_id))
+            }
+            Message::NewRib {
+                workbench_id,
+                rib_name,
+                sketch_id,
+                segment,
+                solid_id,
+                thickness,
+                side,
+                direction,
+            } => {
+                let workbench = project.get_workbench_by_id_mut(*workbench_id)?;
+                let rib = Rib::new(
+                    sketch_id.to_owned(),
+                    segment.to_owned(),
+                    solid_id.to_owned(),
+                    *thickness,
+                    side.to_owned(),
+                    direction.to_owned(),
+                );
+                let rib_id = workbench.add_rib(rib_name, rib);
+                Ok(format!("\"id\": \"{}\"", rib_id))
+            }
+            Message::NewSplit {
+                workbench_id,
+                split_name,
+                solid_id,
+                tool,
+                keep,
+            } => {
+                let workbench = project.get_workbench_by_id_mut(*workbench_id)?;
+                let split = Split::new(solid_id.to_owned(), tool.to_owned(), keep.to_owned());
+                let split_id = workbench.add_split(split_name, split);
+                Ok(format!("\"id\": \"{}\"", split_id))
+            }
+            Message::NewBoolean {
+                workbench_id,
+                boolean_name,
+                target_id,
+                tool_ids,
+                operation,
+                keep_tools,
+            } => {
+                let workbench = project.get_workbench_by_id_mut(*workbench_id)?;
+                let boolean = Boolean::new(
+                    target_id.to_owned(),
+                    tool_ids.to_owned(),
+                    operation.to_owned(),
+                    *keep_tools,
+                );
+                let boolean_id = workbench.add_boolean(boolean_name, boolean);
+                Ok(format!("\"id\": \"{}\"", boolean_id))
+            }
+            Message::NewTransform {
+                workbench_id,
+                transform_name,
+                solid_ids,
+                motion,
+                copy,
+            } => {
+                let workbench = project.get_workbench_by_id_mut(*workbench_id)?;
+                let transform = Transform::new(solid_ids.to_owned(), motion.to_owned(), *copy);
+                let transform_id = workbench.add_transform(transform_name, transform);
+                Ok(format!("\"id\": \"{}\"", transform_id))
+            }
+            Message::NewOffsetFace {
+                workbench_id,
+                offset_face_name,
+                solid_id,
+                face_normals,
+                near,
+                distance,
+            } => {
+                let workbench = project.get_workbench_by_id_mut(*workbench_id)?;
+                let offset_face = OffsetFace::new(
+                    solid_id.to_owned(),
+                    face_normals.to_owned(),
+                    near.to_owned(),
+                    *distance,
+                );
+                let offset_face_id = workbench.add_offset_face(offset_face_name, offset_face);
+                Ok(format!("\"id\": \"{}\"", offset_face_id))
+            }
+            Message::NewAxis {
+                workbench_id,
+                axis_name,
+                axis,
+            } => {
+                let workbench = project.get_workbench_by_id_mut(*workbench_id)?;
+                let axis_id = workbench.add_axis(axis_name, axis.to_owned());
+                Ok(format!("\"id\": \"{}\"", axis_id))
+            }
+            Message::NewDerivedPoint {
+                workbench_id,
+                point_name,
+                point,
+            } => {
+                let workbench = project.get_workbench_by_id_mut(*workbench_id)?;
+                let point_id = workbench.add_derived_point(point_name, point.to_owned());
+                Ok(format!("\"id\": \"{}\"", point_id))
+            }
+            Message::NewDerivedPlane {
+                workbench_id,
+                plane_name,
+                plane,
+            } => {
+                let workbench = project.get_workbench_by_id_mut(*workbench_id)?;
+                let plane_id = workbench.add_derived_plane(plane_name, plane.to_owned());
+                Ok(format!("\"id\": \"{}\"", plane_id))
+            }
         }
     }
 }
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use truck_modeling::{InnerSpace, Matrix3, Matrix4, SquareMatrix, Surface};
+use truck_modeling::{Point3 as TruckPoint3, Vector3 as TruckVector3};
+
+use crate::archetypes::{Point3, Vector3};
+use crate::error::CADmiumError;
+use crate::pattern::PatternSource;
+use crate::realization::Realization;
+
+// The plane that a mirror reflects across
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum MirrorPlane {
+    // the id of a plane in the realization, like "Plane-0"
+    Plane(String),
+    // the planar face of a solid with the given outward normal. If several faces have
+    // that normal, near picks the one closest to it
+    SolidFace {
+        solid_id: String,
+        normal: Vector3,
+        #[serde(default)]
+        near: Option<Point3>,
+    },
+}
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct Mirror {
+    pub sources: Vec<PatternSource>,
+    pub plane: MirrorPlane,
+    // fuse each mirrored solid with the solid it came from. Mirrored step bodies are
+    // always merged the way the step's own bodies were
+    pub merge: bool,
+}
+
+impl Mirror {
+    pub fn new(sources: Vec<PatternSource>, plane: MirrorPlane, merge: bool) -> Self {
+        Mirror {
+            sources,
+            plane,
+            merge,
+        }
+    }
+
+    // The reflection across the mirror plane
+    pub fn transform(&self, realized: &Realization) -> Result<Matrix4, CADmiumError> {
+        let (origin, normal) = match &self.plane {
+            MirrorPlane::Plane(plane_id) => {
+                let plane = &realized
+                    .planes
+                    .get(plane_id)
+                    .ok_or(CADmiumError::PlaneNotFound(plane_id.clone()))?
+                    .plane;
+                (
+                    TruckPoint3::new(plane.origin.x, plane.origin.y, plane.origin.z),
+                    TruckVector3::new(plane.tertiary.x, plane.tertiary.y, plane.tertiary.z),
+                )
+            }
+            MirrorPlane::SolidFace {
+                solid_id,
+                normal,
+                near,
+            } => {
+                let solid = realized
+                    .solids
+                    .get(solid_id)
+                    .ok_or(CADmiumError::SolidNotFound(solid_id.clone()))?;
+                let face = solid.get_face_by_normal(normal, near.as_ref())?;
+                match face.oriented_surface() {
+                    Surface::Plane(plane) => (plane.origin(), plane.normal()),
+                    _ => return Err(CADmiumError::FaceNotFound(solid_id.clone())),
+                }
+            }
+        };
+
+        if normal.magnitude() < 1e-9 {
+            return Err(CADmiumError::GeometryConstructionFailed(
+                "the mirror plane".to_owned(),
+            ));
+        }
+        let n = normal.normalize();
+
+        // flip everything along the normal, then shift it back so that points on the
+        // plane stay put
+        let flip = Matrix3::identity() - Matrix3::from_cols(n * n.x, n * n.y, n * n.z) * 2.0;
+        let shift = n * (2.0 * n.dot(origin - TruckPoint3::new(0.0, 0.0, 0.0)));
+        Ok(Matrix4::from_translation(shift) * Matrix4::from(flip))
+    }
+}
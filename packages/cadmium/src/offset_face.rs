@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use crate::archetypes::{Point3, Vector3};
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct OffsetFace {
+    pub solid_id: String,
+    // the outward normals of the planar faces to move
+    pub face_normals: Vec<Vector3>,
+    // for each normal in turn, a point that picks out the face closest to it when several
+    // faces share that normal. Faces lying in the same plane as the picked one move too
+    #[serde(default)]
+    pub near: Vec<Option<Point3>>,
+    // positive distances push the faces out of the solid, negative ones pull them in
+    pub distance: f64,
+}
+
+impl OffsetFace {
+    pub fn new(
+        solid_id: String,
+        face_normals: Vec<Vector3>,
+        near: Vec<Option<Point3>>,
+        distance: f64,
+    ) -> Self {
+        OffsetFace {
+            solid_id,
+            face_normals,
+            near,
+            distance,
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use std::f64::consts::PI;
+
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use truck_modeling::{InnerSpace, Matrix4, Vector3 as TruckVector3};
+use truck_polymesh::Rad;
+
+use crate::archetypes::{Point3, Vector3};
+use crate::error::CADmiumError;
+use crate::realization::Realization;
+use crate::revolve::RevolveAxis;
+
+// What gets repeated by a pattern
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum PatternSource {
+    // the bodies built by an extrusion or revolve step, like "Extrusion-1". Each copy
+    // gets added, removed or intersected just like the step's own bodies were
+    Step(String),
+    // a whole solid from the realization. Each copy becomes a new solid
+    Solid(String),
+}
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct PatternDirection {
+    pub direction: Vector3,
+    // how many instances there are along this direction, counting the original
+    pub count: u64,
+    pub spacing: f64,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct LinearPattern {
+    pub source: PatternSource,
+    pub first: PatternDirection,
+    // makes the pattern rectangular
+    pub second: Option<PatternDirection>,
+}
+
+impl LinearPattern {
+    pub fn new(
+        source: PatternSource,
+        first: PatternDirection,
+        second: Option<PatternDirection>,
+    ) -> Self {
+        LinearPattern {
+            source,
+            first,
+            second,
+        }
+    }
+
+    // Where each copy goes, relative to the original. The original itself is left out
+    pub fn transforms(&self) -> Vec<Matrix4> {
+        let step = |d: &PatternDirection| {
+            let direction = TruckVector3::new(d.direction.x, d.direction.y, d.direction.z);
+            if direction.magnitude() < 1e-12 {
+                direction
+            } else {
+                direction.normalize() * d.spacing
+            }
+        };
+
+        let (first_step, first_count) = (step(&self.first), self.first.count.max(1));
+        let (second_step, second_count) = match &self.second {
+            Some(second) => (step(second), second.count.max(1)),
+            None => (TruckVector3::new(0.0, 0.0, 0.0), 1),
+        };
+
+        let mut transforms = vec![];
+        for i in 0..first_count {
+            for j in 0..second_count {
+                if i == 0 && j == 0 {
+                    continue;
+                }
+                let shift = first_step * i as f64 + second_step * j as f64;
+                transforms.push(Matrix4::from_translation(shift));
+            }
+        }
+        transforms
+    }
+}
+
+// The line that a circular pattern turns about
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum PatternAxis {
+    // the normal of a plane, through one of the realization's points or else through the
+    // plane's origin
+    PlaneNormal {
+        plane_id: String,
+        point_id: Option<String>,
+    },
+    // a line segment in a sketch, like "Sketch-0"
+    SketchLine {
+        sketch_id: String,
+        line_id: u64,
+    },
+    // a reference axis step, like "Axis-0"
+    Axis(String),
+}
+
+impl PatternAxis {
+    // Returns the origin and unit direction of the axis in 3D
+    pub fn resolve(&self, realized: &Realization) -> Result<(Point3, Vector3), CADmiumError> {
+        match self {
+            PatternAxis::PlaneNormal { plane_id, point_id } => {
+                let plane = realized
+                    .planes
+                    .get(plane_id)
+                    .ok_or(CADmiumError::PlaneNotFound(plane_id.clone()))?;
+                let origin = match point_id {
+                    Some(point_id) => realized
+                        .points
+                        .get(point_id)
+                        .ok_or(CADmiumError::PointNotFound(point_id.clone()))?
+                        .clone(),
+                    None => plane.plane.origin.clone(),
+                };
+                let normal = &plane.plane.tertiary;
+                let length = normal.dot(normal).sqrt();
+                if length < 1e-9 {
+                    return Err(CADmiumError::InvalidAxis(plane_id.clone()));
+                }
+                Ok((origin, normal.times(1.0 / length)))
+            }
+            PatternAxis::SketchLine { sketch_id, line_id } => {
+                let (sketch, _split_sketch, _name) = realized
+                    .sketches
+                    .get(sketch_id)
+                    .ok_or(CADmiumError::InvalidAxis(sketch_id.clone()))?;
+                let plane = realized
+                    .planes
+                    .get(&sketch.plane_id)
+                    .ok_or(CADmiumError::PlaneNotFound(sketch.plane_id.clone()))?;
+                RevolveAxis::SketchLine(*line_id)
+                    .resolve(realized, plane, sketch)
+                    .ok_or(CADmiumError::InvalidAxis(sketch_id.clone()))
+            }
+            PatternAxis::Axis(axis_id) => realized
+                .axes
+                .get(axis_id)
+                .map(|axis| (axis.origin.clone(), axis.direction.clone()))
+                .ok_or(CADmiumError::InvalidAxis(axis_id.clone())),
+        }
+    }
+}
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct CircularPattern {
+    pub source: PatternSource,
+    pub axis: PatternAxis,
+    // how many instances there are, counting the original
+    pub count: u64,
+    // in radians, following the right hand rule about the axis
+    pub angle: f64,
+    // when set the angle is the total that the instances are spread evenly over,
+    // otherwise it is the angle between neighboring instances
+    pub equal_spacing: bool,
+}
+
+impl CircularPattern {
+    pub fn new(
+        source: PatternSource,
+        axis: PatternAxis,
+        count: u64,
+        angle: f64,
+        equal_spacing: bool,
+    ) -> Self {
+        CircularPattern {
+            source,
+            axis,
+            count,
+            angle,
+            equal_spacing,
+        }
+    }
+
+    // Where each copy goes, relative to the original. The original itself is left out
+    pub fn transforms(&self, realized: &Realization) -> Result<Vec<Matrix4>, CADmiumError> {
+        let (origin, axis) = self.axis.resolve(realized)?;
+        let origin = TruckVector3::new(origin.x, origin.y, origin.z);
+        let axis = TruckVector3::new(axis.x, axis.y, axis.z);
+
+        let count = self.count.max(1);
+        let between = if !self.equal_spacing {
+            self.angle
+        } else if self.angle.abs() >= 2.0 * PI - 1e-9 {
+            // around a full turn the last instance would land back on the original
+            self.angle / count as f64
+        } else if count > 1 {
+            self.angle / (count - 1) as f64
+        } else {
+            0.0
+        };
+
+        Ok((1..count)
+            .map(|i| {
+                Matrix4::from_translation(origin)
+                    * Matrix4::from_axis_angle(axis, Rad(between * i as f64))
+                    * Matrix4::from_translation(-origin)
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,186 @@
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use truck_modeling::{BoundedCurve, Curve, Edge, InnerSpace, ParametricCurve};
+use truck_modeling::{Point3 as TruckPoint3, Vector3 as TruckVector3};
+
+use crate::archetypes::Point3;
+use crate::error::CADmiumError;
+use crate::realization::Realization;
+use crate::solid::Solid;
+
+// A reference point that is worked out again every time the workbench is realized.
+// Points on solids are picked by whichever vertex or edge is closest to near, so that
+// they keep following the same feature as the solid changes a little
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum PointDescription {
+    // a point of a sketch, like "Sketch-0", where it sits in 3D
+    SketchPoint { sketch_id: String, point_id: u64 },
+    SolidVertex { solid_id: String, near: Point3 },
+    // halfway along an edge, following the edge even when it is curved
+    EdgeMidpoint { solid_id: String, near: Point3 },
+    // the center of an edge that is a circle or an arc of one
+    CircleCenter { solid_id: String, near: Point3 },
+    // where a reference axis step, like "Axis-0", passes through a plane
+    AxisPlane { axis_id: String, plane_id: String },
+}
+
+impl PointDescription {
+    pub fn resolve(&self, realized: &Realization) -> Result<Point3, CADmiumError> {
+        let truck_point = |p: &Point3| TruckPoint3::new(p.x, p.y, p.z);
+        let solid = |solid_id: &String| {
+            realized
+                .solids
+                .get(solid_id)
+                .ok_or(CADmiumError::SolidNotFound(solid_id.clone()))
+        };
+
+        let point = match self {
+            PointDescription::SketchPoint {
+                sketch_id,
+                point_id,
+            } => {
+                let (sketch, _split_sketch, _name) = realized
+                    .sketches
+                    .get(sketch_id)
+                    .ok_or(CADmiumError::PointNotFound(sketch_id.clone()))?;
+                return sketch
+                    .points
+                    .get(point_id)
+                    .map(|p| Point3::new(p.x, p.y, p.z))
+                    .ok_or(CADmiumError::PointNotFound(point_id.to_string()));
+            }
+            PointDescription::SolidVertex { solid_id, near } => {
+                let near = truck_point(near);
+                solid(solid_id)?
+                    .truck_solid
+                    .vertex_iter()
+                    .map(|v| v.point())
+                    .min_by(|a, b| (a - near).magnitude().total_cmp(&(b - near).magnitude()))
+                    .ok_or(CADmiumError::PointNotFound(solid_id.clone()))?
+            }
+            PointDescription::EdgeMidpoint { solid_id, near } => {
+                let solid = solid(solid_id)?;
+                let edge = closest_edge(solid, truck_point(near), |_| true)?;
+                halfway_along(&edge)
+            }
+            PointDescription::CircleCenter { solid_id, near } => {
+                let solid = solid(solid_id)?;
+                let edge = closest_edge(solid, truck_point(near), |edge| {
+                    circle_center(edge).is_some()
+                })?;
+                circle_center(&edge).ok_or(CADmiumError::EdgeNotFound(solid_id.clone()))?
+            }
+            PointDescription::AxisPlane { axis_id, plane_id } => {
+                let axis = realized
+                    .axes
+                    .get(axis_id)
+                    .ok_or(CADmiumError::InvalidAxis(axis_id.clone()))?;
+                let plane = &realized
+                    .planes
+                    .get(plane_id)
+                    .ok_or(CADmiumError::PlaneNotFound(plane_id.clone()))?
+                    .plane;
+                let direction =
+                    TruckVector3::new(axis.direction.x, axis.direction.y, axis.direction.z);
+                let normal =
+                    TruckVector3::new(plane.tertiary.x, plane.tertiary.y, plane.tertiary.z);
+
+                // an axis that runs along the plane never crosses it
+                let rate = direction.dot(normal);
+                if rate.abs() < 1e-9 {
+                    return Err(CADmiumError::InvalidAxis(axis_id.clone()));
+                }
+                let origin = truck_point(&axis.origin);
+                let height = (truck_point(&plane.origin) - origin).dot(normal);
+                origin + direction * (height / rate)
+            }
+        };
+
+        Ok(Point3::new(point.x, point.y, point.z))
+    }
+}
+
+// The point a fraction t of the way along the edge, by its curve's parameter
+fn point_along(edge: &Edge, t: f64) -> TruckPoint3 {
+    point_on(&edge.oriented_curve(), t)
+}
+
+fn point_on(curve: &Curve, t: f64) -> TruckPoint3 {
+    let (start, end) = curve.range_tuple();
+    curve.subs(start + (end - start) * t)
+}
+
+// The point halfway along the edge by length. The length is measured along chords
+// that cut the edge into short pieces, so it follows curved edges closely
+fn halfway_along(edge: &Edge) -> TruckPoint3 {
+    let pieces = 64;
+    let points: Vec<TruckPoint3> = (0..=pieces)
+        .map(|i| point_along(edge, i as f64 / pieces as f64))
+        .collect();
+    let lengths: Vec<f64> = points
+        .windows(2)
+        .map(|w| (w[1] - w[0]).magnitude())
+        .collect();
+
+    let mut left = lengths.iter().sum::<f64>() / 2.0;
+    for (i, length) in lengths.iter().enumerate() {
+        if left <= *length {
+            let t = if *length > 0.0 { left / length } else { 0.0 };
+            return point_along(edge, (i as f64 + t) / pieces as f64);
+        }
+        left -= length;
+    }
+    points[pieces]
+}
+
+// The edge of the solid that passes closest to near, among those that keep accepts
+fn closest_edge(
+    solid: &Solid,
+    near: TruckPoint3,
+    keep: impl Fn(&Edge) -> bool,
+) -> Result<Edge, CADmiumError> {
+    let distance = |edge: &Edge| {
+        (0..=8)
+            .map(|i| (point_along(edge, i as f64 / 8.0) - near).magnitude())
+            .fold(f64::MAX, f64::min)
+    };
+    solid
+        .truck_solid
+        .edge_iter()
+        .filter(|edge| keep(edge))
+        .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+        .ok_or(CADmiumError::EdgeNotFound(solid.name.clone()))
+}
+
+fn circle_center(edge: &Edge) -> Option<TruckPoint3> {
+    curve_center(&edge.oriented_curve())
+}
+
+// The center of the circle through points spread along the curve, or None when the
+// curve is straight or not round
+pub fn curve_center(curve: &Curve) -> Option<TruckPoint3> {
+    // a whole circle ends where it starts, so the last point stops short of the end
+    let a = point_on(curve, 0.0);
+    let b = point_on(curve, 1.0 / 3.0);
+    let c = point_on(curve, 2.0 / 3.0);
+    let (ab, ac) = (b - a, c - a);
+    let normal = ab.cross(ac);
+    if normal.magnitude2() < 1e-12 * ab.magnitude2() * ac.magnitude2() {
+        return None;
+    }
+
+    let offset = (normal.cross(ab) * ac.magnitude2() + ac.cross(normal) * ab.magnitude2())
+        / (2.0 * normal.magnitude2());
+    let center = a + offset;
+
+    // any curve bends through three points, so a fourth has to agree before it counts as
+    // a circle
+    let radius = offset.magnitude();
+    let d = point_on(curve, 5.0 / 6.0);
+    if ((d - center).magnitude() - radius).abs() > 1e-6 * radius.max(1.0) {
+        return None;
+    }
+    Some(center)
+}
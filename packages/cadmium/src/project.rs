@@ -6,8 +6,9 @@ use crate::archetypes::*;
 use crate::error::CADmiumError;
 use crate::realization::Realization;
 use crate::sketch::constraints::Constraint;
-use crate::sketch::{Face, Point2, Sketch};
+use crate::sketch::{Face, Point2, Segment, Sketch};
 use crate::step::StepData;
+use crate::sweep::PathSegment;
 use crate::workbench::Workbench;
 use std::collections::HashMap;
 
@@ -108,6 +109,8 @@ pub struct RealSketch {
     pub constraints: HashMap<u64, Constraint>,
     pub highest_constraint_id: u64,
     pub faces: Vec<Face>,
+    // the lines and arcs that aren't part of any face, joined end to end
+    pub chains: Vec<Vec<Segment>>,
 }
 
 impl RealSketch {
@@ -130,6 +133,7 @@ impl RealSketch {
             constraints: HashMap::new(),
             highest_constraint_id: 0,
             faces: vec![],
+            chains: vec![],
         };
 
         let o = plane.plane.origin.clone();
@@ -200,11 +204,82 @@ impl RealSketch {
                 .insert(*constraint_id, real_constraint);
         }
 
-        let (faces, _unused_segments) = sketch.find_faces();
+        let (faces, unused_segments) = sketch.find_faces();
+        real_sketch.chains = sketch.find_chains(&unused_segments, &faces);
         real_sketch.faces = faces;
 
         real_sketch
     }
+
+    // The open chain that runs through a given line or arc of the sketch. Chains are
+    // worked out afresh every time the sketch is realized, so they are picked out by a
+    // segment they contain rather than by where they come in the list
+    pub fn chain_through(&self, segment: &PathSegment) -> Option<&Vec<Segment>> {
+        let same_ends = |a: (u64, u64), b: (u64, u64)| a == b || a == (b.1, b.0);
+        self.chains.iter().find(|chain| {
+            chain.iter().any(|s| match (segment, s) {
+                (PathSegment::Line(id), Segment::Line(line)) => self
+                    .line_segments
+                    .get(id)
+                    .is_some_and(|l| same_ends((l.start, l.end), (line.start, line.end))),
+                (PathSegment::Arc(id), Segment::Arc(arc)) => self.arcs.get(id).is_some_and(|a| {
+                    a.center == arc.center && same_ends((a.start, a.end), (arc.start, arc.end))
+                }),
+                _ => false,
+            })
+        })
+    }
+
+    // The line or arc of split, this same sketch with its crossings split apart, that runs
+    // along one of this sketch's segments. Splitting numbers every segment afresh but keeps
+    // the points, so the piece is found by where its ends sit on the segment. A segment
+    // that was cut in several pieces gives the one numbered first
+    pub fn split_piece(&self, split: &RealSketch, segment: &PathSegment) -> Option<PathSegment> {
+        let point = |id: &u64| split.points_2d.get(id);
+        match segment {
+            PathSegment::Line(id) => {
+                let line = self.line_segments.get(id)?;
+                let (a, b) = (point(&line.start)?, point(&line.end)?);
+                let (dx, dy) = (b.x - a.x, b.y - a.y);
+                let length2 = dx * dx + dy * dy;
+                let on_line = |id: &u64| {
+                    point(id).is_some_and(|p| {
+                        let (px, py) = (p.x - a.x, p.y - a.y);
+                        let t = (px * dx + py * dy) / length2;
+                        (px * dy - py * dx).abs() <= 1e-9 * length2 && t > -1e-9 && t < 1.0 + 1e-9
+                    })
+                };
+                split
+                    .line_segments
+                    .iter()
+                    .filter(|(_, piece)| on_line(&piece.start) && on_line(&piece.end))
+                    .map(|(piece_id, _)| *piece_id)
+                    .min()
+                    .map(PathSegment::Line)
+            }
+            PathSegment::Arc(id) => {
+                let arc = self.arcs.get(id)?;
+                let center = point(&arc.center)?;
+                let distance = |id: &u64| point(id).map(|p| (p.x - center.x).hypot(p.y - center.y));
+                let radius = distance(&arc.start)?;
+                let on_arc = |id: &u64| {
+                    distance(id).is_some_and(|d| (d - radius).abs() <= 1e-9 * radius.max(1.0))
+                };
+                split
+                    .arcs
+                    .iter()
+                    .filter(|(_, piece)| {
+                        piece.center == arc.center
+                            && piece.clockwise == arc.clockwise
+                            && on_arc(&piece.start)
+                            && on_arc(&piece.end)
+                    })
+                    .map(|(piece_id, _)| *piece_id)
+                    .min()
+                    .map(PathSegment::Arc)
+            }
+        }
+    }
 }
 
 #[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
@@ -222,7 +297,9 @@ pub mod tests {
 
     use crate::extrusion::Direction;
     use crate::extrusion::Extrusion;
+    use crate::extrusion::ExtrusionEnd;
     use crate::extrusion::ExtrusionMode;
+    use crate::extrusion::{ThinWall, WallSide};
     use crate::message::Message;
     use truck_meshalgo::filters::*;
     use truck_meshalgo::tessellation::*;
@@ -308,6 +385,135 @@ pub mod tests {
         // let realization = p.get_realization(0, 1000);
     }
 
+    // Sends the UI's update for Ext1 of the test project, with a new length, and returns
+    // the extrusion as it is afterwards
+    fn update_ext1_length(p: &mut Project, length: f64) -> Extrusion {
+        let message = Message::UpdateExtrusion {
+            workbench_id: 0,
+            extrusion_name: "Ext1".to_owned(),
+            extrusion_id: "Extrusion-0".to_owned(),
+            sketch_id: "Sketch-0".to_owned(),
+            face_ids: vec![0],
+            length,
+            offset: 0.0,
+            direction: Direction::Normal,
+        };
+        message.handle(p).unwrap();
+
+        match &p.workbenches[0].get_step_mut("Ext1").unwrap().data {
+            StepData::Extrusion { extrusion } => extrusion.clone(),
+            _ => panic!("Ext1 should still be an extrusion"),
+        }
+    }
+
+    #[test]
+    fn update_extrusion_keeps_draft() {
+        let mut p = create_test_project();
+        let message = Message::UpdateExtrusionDraftAngle {
+            workbench_id: 0,
+            extrusion_name: "Ext1".to_owned(),
+            draft_angle: 0.1,
+        };
+        message.handle(&mut p).unwrap();
+
+        let extrusion = update_ext1_length(&mut p, 30.0);
+        assert_eq!(extrusion.length, 30.0);
+        assert_eq!(extrusion.draft_angle, 0.1);
+
+        // the block runs back from the Front plane to y = -30, and its far end is
+        // still pulled in on every side
+        let realization = p.get_realization(0, 1000);
+        let inset = 30.0 * 0.1_f64.tan();
+        let solid = &realization.solids["Ext1:0"];
+        let far_end: Vec<_> = solid
+            .vertices
+            .iter()
+            .filter(|v| (v.y + 30.0).abs() < 1e-6)
+            .collect();
+        assert!(!far_end.is_empty());
+        for v in far_end {
+            assert!(v.x > inset - 1e-6 && v.x < 40.0 - inset + 1e-6);
+            assert!(v.z > inset - 1e-6 && v.z < 40.0 - inset + 1e-6);
+        }
+    }
+
+    #[test]
+    fn update_extrusion_keeps_end() {
+        let mut p = create_test_project();
+        let message = Message::UpdateExtrusionEnd {
+            workbench_id: 0,
+            extrusion_name: "Ext1".to_owned(),
+            end: ExtrusionEnd::Symmetric,
+        };
+        message.handle(&mut p).unwrap();
+
+        let extrusion = update_ext1_length(&mut p, 30.0);
+        assert!(matches!(extrusion.end, ExtrusionEnd::Symmetric));
+
+        // still split evenly across the Front plane
+        let realization = p.get_realization(0, 1000);
+        let solid = &realization.solids["Ext1:0"];
+        let front = solid.vertices.iter().map(|v| v.y).fold(f64::MIN, f64::max);
+        let back = solid.vertices.iter().map(|v| v.y).fold(f64::MAX, f64::min);
+        assert!((front - 15.0).abs() < 1e-6);
+        assert!((back + 15.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn update_extrusion_keeps_second_side() {
+        let mut p = create_test_project();
+        let message = Message::UpdateExtrusionSecondLength {
+            workbench_id: 0,
+            extrusion_name: "Ext1".to_owned(),
+            second_length: 10.0,
+        };
+        message.handle(&mut p).unwrap();
+
+        let extrusion = update_ext1_length(&mut p, 30.0);
+        assert_eq!(extrusion.second_length, 10.0);
+
+        // 30 back from the Front plane and 10 in front of it
+        let realization = p.get_realization(0, 1000);
+        let solid = &realization.solids["Ext1:0"];
+        let front = solid.vertices.iter().map(|v| v.y).fold(f64::MIN, f64::max);
+        let back = solid.vertices.iter().map(|v| v.y).fold(f64::MAX, f64::min);
+        assert!((front - 10.0).abs() < 1e-6);
+        assert!((back + 30.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn update_extrusion_keeps_thin_wall() {
+        let mut p = create_test_project();
+
+        // a loose line off to the side of the square
+        let s = p.workbenches[0].get_sketch_mut("Sketch 1").unwrap();
+        let start = s.add_point(50.0, 0.0);
+        let end = s.add_point(60.0, 0.0);
+        let line = s.add_segment(start, end);
+
+        let message = Message::UpdateExtrusionThinWall {
+            workbench_id: 0,
+            extrusion_name: "Ext1".to_owned(),
+            thin: Some(ThinWall::new(
+                vec![PathSegment::Line(line)],
+                2.0,
+                WallSide::MidPlane,
+            )),
+        };
+        message.handle(&mut p).unwrap();
+
+        let extrusion = update_ext1_length(&mut p, 30.0);
+        assert_eq!(extrusion.thin.map(|thin| thin.thickness), Some(2.0));
+
+        // the wall still comes out next to the block, as deep as the new length
+        let realization = p.get_realization(0, 1000);
+        assert_eq!(realization.solids.len(), 2);
+        let wall = &realization.solids["Ext1:1"];
+        assert!(wall.vertices.iter().all(|v| v.x > 50.0 - 1e-6));
+        let back = wall.vertices.iter().map(|v| v.y).fold(f64::MAX, f64::min);
+        assert!((back + 30.0).abs() < 1e-6);
+    }
+
     // Removed because this seems pretty redundant with all the other tests that read .cadmium files
     // #[test]
     // fn to_and_from_json() {
@@ -2,7 +2,15 @@ use serde::{Deserialize, Serialize};
 use tsify::Tsify;
 use wasm_bindgen::prelude::*;
 
-use crate::archetypes::Point3;
+use truck_modeling::{BoundedCurve, InnerSpace, Matrix3, ParametricCurve, Surface};
+use truck_modeling::{Point3 as TruckPoint3, Vector3 as TruckVector3};
+use truck_polymesh::Rad;
+
+use crate::archetypes::{EdgeDescription, Plane, Point3, Vector3};
+use crate::axis::RealAxis;
+use crate::error::CADmiumError;
+use crate::extrusion::ExtrusionMode;
+use crate::pattern::PatternAxis;
 use crate::project::{RealPlane, RealSketch};
 use crate::solid::Solid;
 use std::collections::HashMap;
@@ -14,8 +22,13 @@ pub struct Realization {
     // history and build a bunch of geometry
     pub planes: HashMap<String, RealPlane>,
     pub points: HashMap<String, Point3>,
+    pub axes: HashMap<String, RealAxis>,
     pub sketches: HashMap<String, (RealSketch, RealSketch, String)>,
     pub solids: HashMap<String, Solid>,
+    // the bodies each extrusion or revolve step built, before they were merged into
+    // anything, keyed by the step's unique id. Patterns repeat these
+    #[serde(skip)]
+    pub tools: HashMap<String, (HashMap<String, Solid>, ExtrusionMode)>,
 }
 
 impl Realization {
@@ -23,8 +36,10 @@ impl Realization {
         Realization {
             planes: HashMap::new(),
             points: HashMap::new(),
+            axes: HashMap::new(),
             sketches: HashMap::new(),
             solids: HashMap::new(),
+            tools: HashMap::new(),
         }
     }
 
@@ -50,3 +65,200 @@ impl Realization {
         solid.save_as_step(filename)
     }
 }
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum PlaneDescription {
+    PlaneId(String),
+    // a planar face of a solid with the given outward normal. When several faces share
+    // it, near picks the one that comes closest to it
+    SolidFace {
+        solid_id: String,
+        normal: Vector3,
+        #[serde(default)]
+        near: Option<Point3>,
+    },
+    // parallel to another plane, moved along its normal
+    Offset {
+        base: Box<PlaneDescription>,
+        distance: f64,
+    },
+    // another plane turned about an axis. In radians, following the right hand rule
+    Angled {
+        base: Box<PlaneDescription>,
+        axis: PatternAxis,
+        angle: f64,
+    },
+    // through three point steps, like "Point-1". Its normal follows the right hand rule
+    // going from the first point to the second and then the third
+    ThreePoints {
+        first: String,
+        second: String,
+        third: String,
+    },
+    // halfway between two planes and facing the same way as the first. Meant for
+    // parallel planes, like the faces on either side of a wall
+    Midplane {
+        first: Box<PlaneDescription>,
+        second: Box<PlaneDescription>,
+    },
+    // square to a line of a sketch, crossing it at a fraction of the way from the
+    // line's start (0.0) to its end (1.0)
+    NormalToLine {
+        sketch_id: String,
+        line_id: u64,
+        at: f64,
+    },
+    // square to an edge of a solid, crossing it a fraction of the way from the edge's
+    // start (0.0) to its end (1.0) by the curve's parameter. The edge may be curved
+    NormalToEdge {
+        solid_id: String,
+        edge: EdgeDescription,
+        at: f64,
+    },
+}
+
+impl PlaneDescription {
+    // Works out where the described plane is right now, so that it follows along when
+    // the geometry it was derived from changes
+    pub fn resolve(&self, realized: &Realization) -> Result<Plane, CADmiumError> {
+        let truck_point = |p: &Point3| TruckPoint3::new(p.x, p.y, p.z);
+        let truck_vector = |v: &Vector3| TruckVector3::new(v.x, v.y, v.z);
+        let point = |id: &String| {
+            realized
+                .points
+                .get(id)
+                .map(truck_point)
+                .ok_or(CADmiumError::PointNotFound(id.clone()))
+        };
+
+        match self {
+            PlaneDescription::PlaneId(plane_id) => Ok(realized
+                .planes
+                .get(plane_id)
+                .ok_or(CADmiumError::PlaneNotFound(plane_id.clone()))?
+                .plane
+                .clone()),
+            PlaneDescription::SolidFace {
+                solid_id,
+                normal,
+                near,
+            } => {
+                let face = realized
+                    .solids
+                    .get(solid_id)
+                    .ok_or(CADmiumError::SolidNotFound(solid_id.clone()))?
+                    .get_face_by_normal(normal, near.as_ref())?;
+                match face.oriented_surface() {
+                    Surface::Plane(p) => Ok(Plane::from_truck(p)),
+                    _ => Err(CADmiumError::UnsupportedShape(solid_id.clone())),
+                }
+            }
+            PlaneDescription::Offset { base, distance } => {
+                let mut plane = base.resolve(realized)?;
+                let normal = truck_vector(&plane.tertiary).normalize() * *distance;
+                let origin = truck_point(&plane.origin) + normal;
+                plane.origin = Point3::new(origin.x, origin.y, origin.z);
+                Ok(plane)
+            }
+            PlaneDescription::Angled { base, axis, angle } => {
+                let base = base.resolve(realized)?;
+                let (center, axis) = axis.resolve(realized)?;
+                let center = truck_point(&center);
+                let turn = Matrix3::from_axis_angle(truck_vector(&axis), Rad(*angle));
+                let origin = center + turn * (truck_point(&base.origin) - center);
+                let turned = |v: &Vector3| {
+                    let v = turn * truck_vector(v);
+                    Vector3::new(v.x, v.y, v.z)
+                };
+                Ok(Plane::new(
+                    Point3::new(origin.x, origin.y, origin.z),
+                    turned(&base.primary),
+                    turned(&base.secondary),
+                    turned(&base.tertiary),
+                ))
+            }
+            PlaneDescription::ThreePoints {
+                first,
+                second,
+                third,
+            } => {
+                let (a, b, c) = (point(first)?, point(second)?, point(third)?);
+                if (b - a).cross(c - a).magnitude() < 1e-9 {
+                    return Err(CADmiumError::InvalidPlane(first.clone()));
+                }
+                Ok(Plane::from_vectors(a, b - a, c - a))
+            }
+            PlaneDescription::Midplane { first, second } => {
+                let mut plane = first.resolve(realized)?;
+                let second = second.resolve(realized)?;
+                let normal = truck_vector(&plane.tertiary).normalize();
+                let gap = normal.dot(truck_point(&second.origin) - truck_point(&plane.origin));
+                let origin = truck_point(&plane.origin) + normal * (gap / 2.0);
+                plane.origin = Point3::new(origin.x, origin.y, origin.z);
+                Ok(plane)
+            }
+            PlaneDescription::NormalToLine {
+                sketch_id,
+                line_id,
+                at,
+            } => {
+                let (sketch, _split_sketch, _name) = realized
+                    .sketches
+                    .get(sketch_id)
+                    .ok_or(CADmiumError::InvalidPlane(sketch_id.clone()))?;
+                let line = sketch
+                    .line_segments
+                    .get(line_id)
+                    .ok_or(CADmiumError::InvalidPlane(sketch_id.clone()))?;
+                let start = truck_point(&sketch.points[&line.start]);
+                let end = truck_point(&sketch.points[&line.end]);
+                let sketch_plane = realized
+                    .planes
+                    .get(&sketch.plane_id)
+                    .ok_or(CADmiumError::PlaneNotFound(sketch.plane_id.clone()))?;
+                if (end - start).magnitude() < 1e-9 {
+                    return Err(CADmiumError::InvalidPlane(sketch_id.clone()));
+                }
+
+                // the line lies in its sketch, so the sketch's normal is square to it
+                let primary = truck_vector(&sketch_plane.plane.tertiary);
+                let secondary = (end - start).cross(primary);
+                Ok(Plane::from_vectors(
+                    start + (end - start) * *at,
+                    primary,
+                    secondary,
+                ))
+            }
+            PlaneDescription::NormalToEdge { solid_id, edge, at } => {
+                let edge = realized
+                    .solids
+                    .get(solid_id)
+                    .ok_or(CADmiumError::SolidNotFound(solid_id.clone()))?
+                    .get_edge(edge)?;
+                let curve = edge.oriented_curve();
+                let (start, end) = curve.range_tuple();
+                let t = start + (end - start) * *at;
+                let tangent = curve.der(t);
+                if tangent.magnitude() < 1e-9 {
+                    return Err(CADmiumError::InvalidPlane(solid_id.clone()));
+                }
+
+                // any direction square to the edge will do for the primary one, so take
+                // the world axis that lies furthest from the edge and square it up
+                let tangent = tangent.normalize();
+                let across = [
+                    TruckVector3::unit_x(),
+                    TruckVector3::unit_y(),
+                    TruckVector3::unit_z(),
+                ]
+                .into_iter()
+                .min_by(|a, b| a.dot(tangent).abs().total_cmp(&b.dot(tangent).abs()))
+                .unwrap();
+                let primary = (across - tangent * across.dot(tangent)).normalize();
+                let secondary = tangent.cross(primary);
+                Ok(Plane::from_vectors(curve.subs(t), primary, secondary))
+            }
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use crate::archetypes::{Point3, Vector3};
+use crate::extrusion::ExtrusionMode;
+use crate::project::{RealPlane, RealSketch};
+use crate::realization::Realization;
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct Revolve {
+    pub sketch_id: String,
+    pub face_ids: Vec<u64>,
+    pub axis: RevolveAxis,
+    pub angle: f64, // in radians, anything >= 2*PI is a full revolution
+    pub mode: ExtrusionMode,
+}
+
+impl Revolve {
+    pub fn new(
+        sketch_id: String,
+        face_ids: Vec<u64>,
+        axis: RevolveAxis,
+        angle: f64,
+        mode: ExtrusionMode,
+    ) -> Self {
+        Revolve {
+            sketch_id,
+            face_ids,
+            axis,
+            angle,
+            mode,
+        }
+    }
+}
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum RevolveAxis {
+    // the id of a line segment in the same sketch as the revolved faces
+    SketchLine(u64),
+    // the primary or secondary axis of the sketch plane, through the plane's origin
+    PlanePrimary,
+    PlaneSecondary,
+    // a reference axis step, like "Axis-0"
+    Axis(String),
+}
+
+impl RevolveAxis {
+    // Returns the origin and unit direction of the axis in 3D, or None if the
+    // referenced line or axis step doesn't exist or has zero length
+    pub fn resolve(
+        &self,
+        realized: &Realization,
+        plane: &RealPlane,
+        sketch: &RealSketch,
+    ) -> Option<(Point3, Vector3)> {
+        let (origin, direction) = match self {
+            RevolveAxis::SketchLine(line_id) => {
+                let line = sketch.line_segments.get(line_id)?;
+                let start = sketch.points.get(&line.start)?;
+                let end = sketch.points.get(&line.end)?;
+                (start.clone(), end.minus(start))
+            }
+            RevolveAxis::PlanePrimary => (plane.plane.origin.clone(), plane.plane.primary.clone()),
+            RevolveAxis::PlaneSecondary => {
+                (plane.plane.origin.clone(), plane.plane.secondary.clone())
+            }
+            RevolveAxis::Axis(axis_id) => {
+                let axis = realized.axes.get(axis_id)?;
+                (axis.origin.clone(), axis.direction.clone())
+            }
+        };
+
+        let length = direction.dot(&direction).sqrt();
+        if length < 1e-9 {
+            return None;
+        }
+
+        Some((origin, direction.times(1.0 / length)))
+    }
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use crate::archetypes::Vector3;
+use crate::extrusion::{Direction, WallSide};
+use crate::project::{RealPlane, RealSketch};
+use crate::sketch::{Point2, Segment};
+use crate::sweep::PathSegment;
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct Rib {
+    pub sketch_id: String,
+    // a line or arc of the open chain in the sketch that the rib grows from
+    pub segment: PathSegment,
+    pub solid_id: String,
+    pub thickness: f64,
+    pub side: WallSide,
+    // the way the rib grows from the sketch until it runs into the solid
+    pub direction: Direction,
+}
+
+impl Rib {
+    pub fn new(
+        sketch_id: String,
+        segment: PathSegment,
+        solid_id: String,
+        thickness: f64,
+        side: WallSide,
+        direction: Direction,
+    ) -> Self {
+        Rib {
+            sketch_id,
+            segment,
+            solid_id,
+            thickness,
+            side,
+            direction,
+        }
+    }
+
+    pub fn direction_vector(&self, plane: &RealPlane) -> Vector3 {
+        match &self.direction {
+            Direction::Normal => plane.plane.tertiary.clone(),
+            Direction::NegativeNormal => plane.plane.tertiary.times(-1.0),
+            Direction::Specified(vector) => vector.clone(),
+        }
+    }
+}
+
+// Returns a copy of the sketch where the chain's straight ends have been pushed out by
+// reach, so that a wall built along it runs all the way through the solid around it
+pub fn extend_chain(
+    plane: &RealPlane,
+    sketch: &RealSketch,
+    chain: &[Segment],
+    reach: f64,
+) -> RealSketch {
+    let mut extended = sketch.clone();
+    let mut push = |point_id: u64, from_id: u64| {
+        let (point, from) = (&sketch.points_2d[&point_id], &sketch.points_2d[&from_id]);
+        let (dx, dy) = (point.x - from.x, point.y - from.y);
+        let length = dx.hypot(dy);
+        if length < 1e-12 {
+            return;
+        }
+        let moved = Point2::new(point.x + dx / length * reach, point.y + dy / length * reach);
+        extended
+            .points
+            .insert(point_id, plane.plane.unproject(&moved));
+        extended.points_2d.insert(point_id, moved);
+    };
+
+    if let Some(Segment::Line(line)) = chain.first() {
+        push(line.start, line.end);
+    }
+    if let Some(Segment::Line(line)) = chain.last() {
+        push(line.end, line.start);
+    }
+
+    extended
+}
@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use truck_modeling::{builder, Curve, Edge, Face, InnerSpace, Matrix3, SquareMatrix, Surface};
+use truck_modeling::{Point3 as TruckPoint3, Solid as TruckSolid, Vector3 as TruckVector3, Wire};
+
+use crate::archetypes::Vector3;
+use crate::error::CADmiumError;
+use crate::solid::Solid;
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct Shell {
+    pub solid_id: String,
+    // measured inward from every face that isn't open
+    pub thickness: f64,
+    // the outward normals of the planar faces to leave open
+    pub open_faces: Vec<Vector3>,
+}
+
+impl Shell {
+    pub fn new(solid_id: String, thickness: f64, open_faces: Vec<Vector3>) -> Self {
+        Shell {
+            solid_id,
+            thickness,
+            open_faces,
+        }
+    }
+}
+
+// Builds the cavity that gets cut out of the solid to hollow it. Every face of the solid
+// is moved inward by the wall thickness, except the open faces, which are moved outward
+// so that the cavity breaks through them. Only solids with flat faces and straight
+// edges can be shelled this way.
+pub fn cavity(solid: &Solid, shell: &Shell) -> Result<TruckSolid, CADmiumError> {
+    let wanted: Vec<TruckVector3> = shell
+        .open_faces
+        .iter()
+        .map(|open| TruckVector3::new(open.x, open.y, open.z).normalize())
+        .collect();
+    let is_open = |normal: TruckVector3| {
+        wanted
+            .iter()
+            .any(|open| (normal - open).magnitude() < 0.0001)
+    };
+    if !wanted.iter().all(|open| has_face(solid, *open)) {
+        return Err(CADmiumError::FaceNotFound(solid.name.clone()));
+    }
+
+    move_faces(solid, |normal, _| {
+        if is_open(normal) {
+            shell.thickness
+        } else {
+            -shell.thickness
+        }
+    })
+}
+
+// Whether the solid has a planar face with this outward normal
+pub fn has_face(solid: &Solid, normal: TruckVector3) -> bool {
+    solid
+        .truck_solid
+        .face_iter()
+        .any(|face| match face.oriented_surface() {
+            Surface::Plane(plane) => (plane.normal() - normal).magnitude() < 0.0001,
+            _ => false,
+        })
+}
+
+// The outward normal of a planar face, and how far along it the face's plane sits from
+// the origin
+pub fn plane_of(face: &Face) -> Option<(TruckVector3, f64)> {
+    match face.oriented_surface() {
+        Surface::Plane(plane) => {
+            let normal = plane.normal();
+            Some((
+                normal,
+                normal.dot(plane.origin() - TruckPoint3::new(0.0, 0.0, 0.0)),
+            ))
+        }
+        _ => None,
+    }
+}
+
+// Rebuilds the solid with each face moved along its outward normal by the distance that
+// shift gives for it from its normal and height, and every edge and corner moved to where
+// the moved faces around it meet. The faces keep their shape, so they can't be moved so
+// far that one of their neighbors shrinks away. Only solids with flat faces and straight
+// edges can be moved this way.
+pub fn move_faces(
+    solid: &Solid,
+    shift: impl Fn(TruckVector3, f64) -> f64,
+) -> Result<TruckSolid, CADmiumError> {
+    let boundaries = solid.truck_solid.boundaries();
+    if boundaries.len() != 1 {
+        return Err(CADmiumError::UnsupportedShape(solid.name.clone()));
+    }
+    let faces: Vec<&Face> = boundaries[0].face_iter().collect();
+
+    // the outward normal of each face and how far along it the face's plane sits
+    // once it has been moved
+    let mut planes: Vec<(TruckVector3, f64)> = vec![];
+    for face in faces.iter() {
+        let (normal, height) =
+            plane_of(face).ok_or(CADmiumError::UnsupportedShape(solid.name.clone()))?;
+        planes.push((normal, height + shift(normal, height)));
+    }
+
+    // every corner moves to where the moved planes of the faces around it meet. With
+    // more than three faces at a corner this is the least squares answer. Booleans
+    // often leave a flat face split into several faces, and those only count once
+    let mut corner_faces: HashMap<_, (TruckPoint3, Vec<usize>)> = HashMap::new();
+    for (face_index, face) in faces.iter().enumerate() {
+        for vertex in face.vertex_iter() {
+            corner_faces
+                .entry(vertex.id())
+                .or_insert((vertex.point(), vec![]))
+                .1
+                .push(face_index);
+        }
+    }
+
+    let mut corners = HashMap::new();
+    for (id, (point, face_indices)) in corner_faces.iter() {
+        let mut constraints: Vec<(TruckVector3, f64)> = vec![];
+        for face_index in face_indices {
+            let (n, height) = planes[*face_index];
+            if !constraints
+                .iter()
+                .any(|(m, h)| (n - m).magnitude() < 1e-6 && (height - h).abs() < 1e-6)
+            {
+                constraints.push((n, height));
+            }
+        }
+
+        // a corner where split faces meet along a straight edge, or in the middle of a
+        // flat face, keeps its place along the directions those faces leave free
+        let position = *point - TruckPoint3::new(0.0, 0.0, 0.0);
+        let free: Vec<TruckVector3> = match constraints.as_slice() {
+            [(n, _)] => {
+                let across = if n.x.abs() < 0.9 {
+                    TruckVector3::unit_x()
+                } else {
+                    TruckVector3::unit_y()
+                };
+                let u = n.cross(across).normalize();
+                vec![u, n.cross(u)]
+            }
+            [(n0, _), (n1, _)] => vec![n0.cross(*n1).normalize()],
+            _ => vec![],
+        };
+        constraints.extend(free.into_iter().map(|d| (d, d.dot(position))));
+
+        let mut normal_matrix = Matrix3::from_value(0.0);
+        let mut right_side = TruckVector3::new(0.0, 0.0, 0.0);
+        for (n, height) in constraints {
+            normal_matrix += Matrix3::from_cols(n * n.x, n * n.y, n * n.z);
+            right_side += n * height;
+        }
+        let solved = normal_matrix
+            .invert()
+            .ok_or(CADmiumError::UnsupportedShape(solid.name.clone()))?
+            * right_side;
+        corners.insert(
+            *id,
+            builder::vertex(TruckPoint3::new(solved.x, solved.y, solved.z)),
+        );
+    }
+
+    // rebuild every face from straight edges between the moved corners, sharing the
+    // edges between neighboring faces just like the original solid does
+    let mut edges: HashMap<_, Edge> = HashMap::new();
+    let mut moved_faces = vec![];
+    for face in faces.iter() {
+        let mut wires = vec![];
+        for boundary in face.boundaries() {
+            let mut wire = Wire::new();
+            for edge in boundary.edge_iter() {
+                if !matches!(edge.curve(), Curve::Line(_)) {
+                    return Err(CADmiumError::UnsupportedShape(solid.name.clone()));
+                }
+                let moved = edges.entry(edge.id()).or_insert_with(|| {
+                    builder::line(
+                        &corners[&edge.absolute_front().id()],
+                        &corners[&edge.absolute_back().id()],
+                    )
+                });
+                wire.push_back(if edge.orientation() {
+                    moved.clone()
+                } else {
+                    moved.inverse()
+                });
+            }
+            wires.push(wire);
+        }
+
+        let moved_face = builder::try_attach_plane(&wires)
+            .map_err(|_| CADmiumError::GeometryConstructionFailed(solid.name.clone()))?;
+        moved_faces.push(moved_face);
+    }
+
+    TruckSolid::try_new(vec![moved_faces.into_iter().collect()])
+        .map_err(|_| CADmiumError::GeometryConstructionFailed(solid.name.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::extrusion::polygon_prism;
+
+    use super::*;
+
+    #[test]
+    fn cavity_with_split_side_face() {
+        // a 40x40x25 block whose front side is two faces in the same plane, split at x = 20
+        let outline: Vec<TruckPoint3> = [
+            (0.0, 0.0),
+            (20.0, 0.0),
+            (40.0, 0.0),
+            (40.0, 40.0),
+            (0.0, 40.0),
+        ]
+        .iter()
+        .map(|(x, y)| TruckPoint3::new(*x, *y, 0.0))
+        .collect();
+        let block = polygon_prism(&outline, TruckVector3::new(0.0, 0.0, 25.0)).unwrap();
+        let solid = Solid::from_truck_solid("Block".to_owned(), block);
+
+        let shell = Shell::new("Block".to_owned(), 2.0, vec![Vector3::new(0.0, 0.0, 1.0)]);
+        let inside = cavity(&solid, &shell).unwrap();
+
+        // the split doesn't move: the corners on it stay at x = 20, on the moved front face
+        for p in inside.vertex_iter().map(|v| v.point()) {
+            assert!(p.x > 2.0 - 1e-9 && p.x < 38.0 + 1e-9);
+            assert!(p.y > 2.0 - 1e-9 && p.y < 38.0 + 1e-9);
+            assert!((p.z - 2.0).abs() < 1e-9 || (p.z - 27.0).abs() < 1e-9);
+        }
+        assert!(inside
+            .vertex_iter()
+            .any(|v| (v.point().x - 20.0).abs() < 1e-9 && (v.point().y - 2.0).abs() < 1e-9));
+    }
+}
@@ -1032,6 +1032,97 @@ impl Sketch {
         (faces, unused_segments)
     }
 
+    // Joins the segments that aren't part of any face into open chains, each one running
+    // head to tail in the direction its first segment was drawn. Chains stop at loose
+    // ends and at points where more than two of those segments meet, so that which way
+    // a chain goes at a branch never depends on the order the segments were drawn in
+    pub fn find_chains(&self, segments: &Vec<Segment>, faces: &Vec<Face>) -> Vec<Vec<Segment>> {
+        let in_a_face = |segment: &Segment| {
+            faces
+                .iter()
+                .flat_map(|face| std::iter::once(&face.exterior).chain(face.holes.iter()))
+                .any(|ring| match ring {
+                    Ring::Segments(ring_segments) => ring_segments
+                        .iter()
+                        .any(|s| s == segment || s.reverse_equals(segment)),
+                    Ring::Circle(_) => false,
+                })
+        };
+
+        let mut remaining: Vec<Segment> = segments
+            .iter()
+            .filter(|segment| !in_a_face(segment))
+            .cloned()
+            .collect();
+        remaining.sort_by_key(|s| {
+            (
+                s.get_start().min(s.get_end()),
+                s.get_start().max(s.get_end()),
+            )
+        });
+
+        let mut meeting: HashMap<u64, usize> = HashMap::new();
+        for segment in remaining.iter() {
+            *meeting.entry(segment.get_start()).or_insert(0) += 1;
+            *meeting.entry(segment.get_end()).or_insert(0) += 1;
+        }
+        let branches = |point: u64| meeting[&point] > 2;
+
+        let mut chains = vec![];
+        while !remaining.is_empty() {
+            let mut chain = vec![remaining.remove(0)];
+
+            // grow the chain forward from its end, then backward from its start
+            loop {
+                let end = chain.last().unwrap().get_end();
+                if end == chain[0].get_start() || branches(end) {
+                    break;
+                }
+                match remaining
+                    .iter()
+                    .position(|s| s.get_start() == end || s.get_end() == end)
+                {
+                    Some(index) => {
+                        let next = remaining.remove(index);
+                        chain.push(if next.get_start() == end {
+                            next
+                        } else {
+                            next.reverse()
+                        });
+                    }
+                    None => break,
+                }
+            }
+            loop {
+                let start = chain[0].get_start();
+                if start == chain.last().unwrap().get_end() || branches(start) {
+                    break;
+                }
+                match remaining
+                    .iter()
+                    .position(|s| s.get_start() == start || s.get_end() == start)
+                {
+                    Some(index) => {
+                        let previous = remaining.remove(index);
+                        chain.insert(
+                            0,
+                            if previous.get_end() == start {
+                                previous
+                            } else {
+                                previous.reverse()
+                            },
+                        );
+                    }
+                    None => break,
+                }
+            }
+
+            chains.push(chain);
+        }
+
+        chains
+    }
+
     pub fn find_rings(&self, segments: Vec<Segment>, debug: bool) -> (Vec<Ring>, Vec<Segment>) {
         // We are handed all of the segments to consider
         let mut segments_overall = segments.clone();
@@ -1889,6 +1980,25 @@ mod tests {
         assert_eq!(sketch.points.len(), 0);
     }
 
+    #[test]
+    fn chains_stop_at_branches() {
+        let mut sketch = Sketch::new();
+
+        // a T: two arms and a stem all meeting at the middle point
+        let left = sketch.add_point(0.0, 0.0);
+        let middle = sketch.add_point(1.0, 0.0);
+        let right = sketch.add_point(2.0, 0.0);
+        let bottom = sketch.add_point(1.0, -1.0);
+        sketch.add_segment(left, middle);
+        sketch.add_segment(middle, right);
+        sketch.add_segment(middle, bottom);
+
+        let (faces, unused_segments) = sketch.find_faces();
+        let chains = sketch.find_chains(&unused_segments, &faces);
+        assert_eq!(chains.len(), 3);
+        assert!(chains.iter().all(|chain| chain.len() == 1));
+    }
+
     #[test]
     fn delete_circles() {
         let mut sketch = Sketch::new();
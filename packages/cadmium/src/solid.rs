@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 use std::f64::consts::PI;
 
+use geo::Contains;
+
 use serde::{Deserialize, Serialize};
 use tsify::Tsify;
 
@@ -11,21 +13,54 @@ use truck_polymesh::obj;
 use truck_polymesh::Rad;
 use truck_stepio::out;
 
-use crate::archetypes::Vector3;
+use crate::archetypes::{EdgeDescription, Point3, Vector3};
+use crate::chamfer::{chamfer_tool, Chamfer};
+use crate::draft::{draft_tools, Draft};
+use crate::error::CADmiumError;
 use crate::extrusion::find_transit;
+use crate::extrusion::fuse;
 use crate::extrusion::merge_faces;
-use crate::extrusion::Direction;
-use crate::extrusion::Extrusion;
+use crate::extrusion::subtract;
+use crate::extrusion::{intersect, polygon_prism, WallSide};
+use crate::extrusion::{left_at, offset_chain, points_extent, Extrusion, ThinWall};
+use crate::extrusion::{offset_keeps_ring, offset_ring, ring_is_counterclockwise};
+use crate::fillet::{fillet_tool, Fillet};
+use crate::hole::{hole_tool, Hole, HoleCallout};
+use crate::loft::{line_up, loft_solid, outline, Loft};
+use crate::offset_face::OffsetFace;
 use crate::project::{RealPlane, RealSketch};
-use crate::sketch::Vector2;
+use crate::realization::Realization;
+use crate::revolve::Revolve;
+use crate::rib::{extend_chain, Rib};
+use crate::shell::{cavity, move_faces, plane_of, Shell};
 use crate::sketch::{Face, Ring, Segment};
+use crate::sketch::{Point2, Vector2};
+use crate::split::{chain_turning, Split, SplitKeep, SplitTool};
+use crate::sweep::{PathPiece, Sweep, SweepOrientation};
 
-use truck_modeling::{builder, builder::translated, Edge, Face as TruckFace, Vertex, Wire};
+use truck_modeling::{
+    builder, builder::translated, BoundedCurve, Edge, Face as TruckFace, InnerSpace,
+    ParametricCurve, Shell as TruckShell, Surface, Vertex, Wire,
+};
+use truck_modeling::{Matrix4, SquareMatrix, Vector4};
 
 use truck_polymesh::Point3 as TruckPoint3;
 use truck_polymesh::Vector3 as TruckVector3;
 use truck_topology::Solid as TruckSolid;
 
+// How a straight edge sits between the two planar faces that meet at it
+#[derive(Debug, Clone)]
+pub struct EdgeCorner {
+    pub solid_name: String,
+    pub start: TruckPoint3,
+    pub end: TruckPoint3,
+    // the outward normals of the two faces
+    pub normals: (TruckVector3, TruckVector3),
+    // unit vectors perpendicular to the edge which point across each face, away from the edge
+    pub inward: (TruckVector3, TruckVector3),
+    pub convex: bool,
+}
+
 #[derive(Tsify, Debug, Serialize, Deserialize, Clone)]
 #[tsify(into_wasm_abi, from_wasm_abi)]
 pub struct Solid {
@@ -41,6 +76,7 @@ pub struct Solid {
         truck_modeling::Curve,
         truck_modeling::Surface,
     >,
+    pub holes: Vec<HoleCallout>,
 }
 
 impl Solid {
@@ -61,6 +97,7 @@ impl Solid {
             uvs: vec![],
             indices: vec![],
             truck_solid,
+            holes: vec![],
         };
         let mesh = solid.truck_solid.triangulation(0.01).to_polygon();
 
@@ -102,13 +139,70 @@ impl Solid {
         solid
     }
 
-    pub fn get_face_by_normal(&self, normal: &Vector3) -> Option<TruckFace> {
+    // Rebuilds this solid around new geometry, keeping its name and metadata
+    pub fn with_truck_solid(
+        &self,
+        truck_solid: TruckSolid<TruckPoint3, truck_modeling::Curve, Surface>,
+    ) -> Self {
+        let mut solid = Solid::from_truck_solid(self.name.clone(), truck_solid);
+        solid.holes = self.holes.clone();
+        solid
+    }
+
+    // Moves this solid and its hole callouts by a rigid transform or a reflection, keeping
+    // its name
+    pub fn transformed(&self, matrix: Matrix4) -> Self {
+        let mut moved = builder::transformed(&self.truck_solid, matrix);
+        // reflections turn solids inside out
+        if matrix.determinant() < 0.0 {
+            moved.not();
+        }
+        let mut solid = self.with_truck_solid(moved);
+        let apply = |x: f64, y: f64, z: f64, w: f64| {
+            let moved = matrix * Vector4::new(x, y, z, w);
+            (moved.x, moved.y, moved.z)
+        };
+        for callout in solid.holes.iter_mut() {
+            let (x, y, z) = apply(callout.center.x, callout.center.y, callout.center.z, 1.0);
+            callout.center = Point3::new(x, y, z);
+            let (x, y, z) = apply(
+                callout.direction.x,
+                callout.direction.y,
+                callout.direction.z,
+                0.0,
+            );
+            callout.direction = Vector3::new(x, y, z);
+            let (x, y, z) = apply(callout.radial.x, callout.radial.y, callout.radial.z, 0.0);
+            callout.radial = Vector3::new(x, y, z);
+        }
+        solid
+    }
+
+    // A cut can leave a solid's boundary in several separate pieces. Returns one solid
+    // per separate body, largest first, each with this solid's name and metadata
+    pub fn split_bodies(&self) -> Vec<Self> {
+        if self.truck_solid.boundaries().len() == 1 {
+            return vec![self.clone()];
+        }
+
+        split_shells(&self.truck_solid)
+            .into_iter()
+            .map(|body| self.with_truck_solid(body))
+            .collect()
+    }
+
+    // The planar face with the given outward normal. When several faces share it, like
+    // the two levels of a stepped top, near picks the one that comes closest to it
+    pub fn get_face_by_normal(
+        &self,
+        normal: &Vector3,
+        near: Option<&Point3>,
+    ) -> Result<TruckFace, CADmiumError> {
         let truck_solid = &self.truck_solid;
-        let boundaries = &truck_solid.boundaries()[0];
 
         let mut candidate_faces: Vec<TruckFace> = vec![];
 
-        boundaries.face_iter().for_each(|face| {
+        truck_solid.face_iter().for_each(|face| {
             let oriented_surface = face.oriented_surface();
 
             match oriented_surface {
@@ -126,13 +220,448 @@ impl Solid {
             }
         });
 
-        match candidate_faces.len() {
-            0 => None,
-            1 => Some(candidate_faces[0].clone()),
-            _ => panic!("More than one face with the same normal!"),
+        match (candidate_faces.len(), near) {
+            (0, _) => Err(CADmiumError::FaceNotFound(self.name.clone())),
+            (1, _) => Ok(candidate_faces.remove(0)),
+            (_, None) => Err(CADmiumError::AmbiguousFace(self.name.clone())),
+            (_, Some(near)) => {
+                let near = TruckPoint3::new(near.x, near.y, near.z);
+                let distance = |face: &TruckFace| {
+                    // how far near is from the box around the face
+                    let (low, high) = points_extent(face.vertex_iter().map(|v| v.point()));
+                    let inside = TruckPoint3::new(
+                        near.x.clamp(low.x, high.x),
+                        near.y.clamp(low.y, high.y),
+                        near.z.clamp(low.z, high.z),
+                    );
+                    (near - inside).magnitude()
+                };
+                Ok(candidate_faces
+                    .into_iter()
+                    .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+                    .unwrap())
+            }
         }
     }
 
+    // The edge of the solid that runs between the description's end points, turned to
+    // run from its start to its end
+    pub fn get_edge(&self, edge: &EdgeDescription) -> Result<Edge, CADmiumError> {
+        let start = TruckPoint3::new(edge.start.x, edge.start.y, edge.start.z);
+        let end = TruckPoint3::new(edge.end.x, edge.end.y, edge.end.z);
+        let close = |a: TruckPoint3, b: TruckPoint3| (a - b).magnitude() < 0.0001;
+
+        self.truck_solid
+            .edge_iter()
+            .find_map(|e| {
+                let (front, back) = (e.front().point(), e.back().point());
+                if close(front, start) && close(back, end) {
+                    Some(e)
+                } else if close(front, end) && close(back, start) {
+                    Some(e.inverse())
+                } else {
+                    None
+                }
+            })
+            .ok_or(CADmiumError::EdgeNotFound(self.name.clone()))
+    }
+
+    pub fn get_edge_corner(&self, edge: &EdgeDescription) -> Result<EdgeCorner, CADmiumError> {
+        let start = TruckPoint3::new(edge.start.x, edge.start.y, edge.start.z);
+        let end = TruckPoint3::new(edge.end.x, edge.end.y, edge.end.z);
+        let close = |a: TruckPoint3, b: TruckPoint3| (a - b).magnitude() < 0.0001;
+
+        // every face that uses this edge, along with the direction the face's
+        // boundary runs along it
+        let mut sides: Vec<(Surface, TruckVector3)> = vec![];
+        for face in self.truck_solid.face_iter() {
+            for oriented_edge in face.edge_iter() {
+                let front = oriented_edge.front().point();
+                let back = oriented_edge.back().point();
+                if (close(front, start) && close(back, end))
+                    || (close(front, end) && close(back, start))
+                {
+                    sides.push((face.oriented_surface(), back - front));
+                }
+            }
+        }
+
+        let (normal_0, direction_0, normal_1, direction_1) = match sides.as_slice() {
+            [] => return Err(CADmiumError::EdgeNotFound(self.name.clone())),
+            [(Surface::Plane(p0), d0), (Surface::Plane(p1), d1)] => {
+                (p0.normal(), *d0, p1.normal(), *d1)
+            }
+            _ => return Err(CADmiumError::UnsupportedEdge(self.name.clone())),
+        };
+
+        // the boundary of a face runs counterclockwise when seen from outside, so the
+        // face itself always lies to the left of the edge
+        let inward_0 = normal_0.cross(direction_0).normalize();
+        let inward_1 = normal_1.cross(direction_1).normalize();
+
+        Ok(EdgeCorner {
+            solid_name: self.name.clone(),
+            start,
+            end,
+            normals: (normal_0, normal_1),
+            inward: (inward_0, inward_1),
+            convex: inward_0.dot(normal_1) < 0.0,
+        })
+    }
+
+    pub fn from_fillet(solid: &Solid, fillet: &Fillet) -> Result<Self, CADmiumError> {
+        // all of the tools are built up front, because the selected edges can't be
+        // found on the solid anymore once the first one has been rounded off
+        let tools = fillet
+            .edges
+            .iter()
+            .map(|edge| {
+                let corner = solid.get_edge_corner(edge)?;
+                Ok((corner.convex, fillet_tool(&corner, fillet.radius)?))
+            })
+            .collect::<Result<Vec<_>, CADmiumError>>()?;
+
+        Self::apply_tools(solid, tools)
+    }
+
+    pub fn from_chamfer(solid: &Solid, chamfer: &Chamfer) -> Result<Self, CADmiumError> {
+        let tools = chamfer
+            .edges
+            .iter()
+            .map(|edge| {
+                let corner = solid.get_edge_corner(edge)?;
+                Ok((corner.convex, chamfer_tool(&corner, &chamfer.mode)?))
+            })
+            .collect::<Result<Vec<_>, CADmiumError>>()?;
+
+        Self::apply_tools(solid, tools)
+    }
+
+    pub fn from_draft(
+        solid: &Solid,
+        draft: &Draft,
+        neutral: &RealPlane,
+    ) -> Result<Self, CADmiumError> {
+        let tools = draft_tools(solid, draft, neutral)?;
+        Self::apply_tools(solid, tools)
+    }
+
+    pub fn from_shell(solid: &Solid, shell: &Shell) -> Result<Self, CADmiumError> {
+        let inside = cavity(solid, shell)?;
+        let hollowed = subtract(&solid.truck_solid, &inside)
+            .ok_or(CADmiumError::BooleanFailed(solid.name.clone()))?;
+        Ok(solid.with_truck_solid(hollowed))
+    }
+
+    pub fn from_offset_face(solid: &Solid, offset: &OffsetFace) -> Result<Self, CADmiumError> {
+        // each selection picks out one plane of the solid, and every face lying in it
+        // moves. A boolean can leave a flat face split into several coplanar faces
+        let mut targets: Vec<(TruckVector3, f64)> = vec![];
+        for (index, normal) in offset.face_normals.iter().enumerate() {
+            let wanted = TruckVector3::new(normal.x, normal.y, normal.z).normalize();
+            let height = match offset.near.get(index).and_then(|near| near.as_ref()) {
+                Some(near) => {
+                    let face = solid.get_face_by_normal(normal, Some(near))?;
+                    plane_of(&face)
+                        .ok_or(CADmiumError::FaceNotFound(solid.name.clone()))?
+                        .1
+                }
+                None => {
+                    let heights: Vec<f64> = solid
+                        .truck_solid
+                        .face_iter()
+                        .filter_map(plane_of)
+                        .filter(|(n, _)| (*n - wanted).magnitude() < 0.0001)
+                        .map(|(_, height)| height)
+                        .collect();
+                    let first = *heights
+                        .first()
+                        .ok_or(CADmiumError::FaceNotFound(solid.name.clone()))?;
+                    if heights.iter().any(|height| (height - first).abs() > 1e-6) {
+                        return Err(CADmiumError::AmbiguousFace(solid.name.clone()));
+                    }
+                    first
+                }
+            };
+            targets.push((wanted, height));
+        }
+
+        let moved = move_faces(solid, |normal, height| {
+            let moving = targets
+                .iter()
+                .any(|(n, h)| (normal - n).magnitude() < 0.0001 && (height - h).abs() < 1e-6);
+            if moving {
+                offset.distance
+            } else {
+                0.0
+            }
+        })?;
+        Ok(solid.with_truck_solid(moved))
+    }
+
+    // Grows a wall out of the rib's chain until it meets the solid on every side, and
+    // fuses it in
+    pub fn from_rib(
+        solid: &Solid,
+        rib: &Rib,
+        plane: &RealPlane,
+        sketch: &RealSketch,
+    ) -> Result<Self, CADmiumError> {
+        let chain = sketch
+            .chain_through(&rib.segment)
+            .ok_or(CADmiumError::ChainNotFound(rib.sketch_id.clone()))?;
+
+        // an oversized wall that reaches right through the solid, from anywhere near it
+        let (low, high) = points_extent(solid.truck_solid.vertex_iter().map(|v| v.point()));
+        let reach = 2.0 * (high - low).magnitude() + rib.thickness;
+        let extended = extend_chain(plane, sketch, chain, reach);
+        let wall = ThinWall::new(vec![], rib.thickness, rib.side.clone());
+        let face = Self::thin_face(&solid.name, plane, &extended, chain, &wall)?;
+
+        let direction = rib.direction_vector(plane);
+        let vector = TruckVector3::new(direction.x, direction.y, direction.z).normalize() * reach;
+        let face = match face.oriented_surface() {
+            Surface::Plane(p) if p.normal().dot(vector) < 0.0 => face.inverse(),
+            _ => face,
+        };
+        let slab = builder::tsweep(&face, vector);
+
+        // the solid chops the slab into pieces, and the rib is the piece that the
+        // sketched chain runs along. A point halfway across the wall from the middle of
+        // the chain's first segment lies on that piece's face in the sketch plane
+        let outside = subtract(&slab, &solid.truck_solid)
+            .ok_or(CADmiumError::BooleanFailed(solid.name.clone()))?;
+        let middle = match &chain[0] {
+            Segment::Line(line) => {
+                let (start, end) = (&sketch.points_2d[&line.start], &sketch.points_2d[&line.end]);
+                Point2::new((start.x + end.x) / 2.0, (start.y + end.y) / 2.0)
+            }
+            Segment::Arc(arc) => plane.plane.project(&find_transit(
+                plane,
+                &sketch.points[&arc.start],
+                &sketch.points[&arc.end],
+                &sketch.points[&arc.center],
+                arc.clockwise,
+            )),
+        };
+        let (left, right) = wall.offsets();
+        let (nx, ny) = left_at(&chain[0], &middle, &sketch.points_2d);
+        let across = (left + right) / 2.0;
+        let inside = Point2::new(middle.x + nx * across, middle.y + ny * across);
+
+        let rib_shells: Vec<_> = outside
+            .boundaries()
+            .iter()
+            .filter(|shell| {
+                shell_volume(shell) > 0.0
+                    && shell
+                        .face_iter()
+                        .any(|face| plane_face_contains(face, plane, &inside))
+            })
+            .cloned()
+            .collect();
+        if rib_shells.is_empty() {
+            return Err(CADmiumError::GeometryConstructionFailed(solid.name.clone()));
+        }
+
+        // if the rib runs all the way out to the far end of the slab, then the chain
+        // never meets the solid in that direction
+        let origin = TruckPoint3::new(
+            plane.plane.origin.x,
+            plane.plane.origin.y,
+            plane.plane.origin.z,
+        );
+        let along = vector.normalize();
+        let reaches_far_end = rib_shells.iter().any(|shell| {
+            shell
+                .vertex_iter()
+                .any(|v| (v.point() - origin).dot(along) > reach - 1e-6)
+        });
+        if reaches_far_end {
+            return Err(CADmiumError::RibMissesSolid(solid.name.clone()));
+        }
+
+        match fuse(&solid.truck_solid, &TruckSolid::new(rib_shells)) {
+            Some(mut bodies) if bodies.len() == 1 => Ok(solid.with_truck_solid(bodies.remove(0))),
+            _ => Err(CADmiumError::BooleanFailed(solid.name.clone())),
+        }
+    }
+
+    // Cuts the solid in two with the split's tool. The piece in front of the tool is
+    // named "{name}:0" and the piece behind it "{name}:1", and only the pieces that the
+    // split keeps are returned
+    pub fn from_split(
+        name: String,
+        solid: &Solid,
+        split: &Split,
+        realized: &Realization,
+    ) -> Result<HashMap<String, Self>, CADmiumError> {
+        let (low, high) = points_extent(solid.truck_solid.vertex_iter().map(|v| v.point()));
+        let center = low + (high - low) / 2.0;
+        let size = (high - low).magnitude();
+        let truck_point = |p: &Point3| TruckPoint3::new(p.x, p.y, p.z);
+        let truck_vector = |v: &Vector3| TruckVector3::new(v.x, v.y, v.z).normalize();
+
+        // a block that fills everything on one side of the tool, at least as far out as
+        // the solid goes, and whether that side is the front
+        let (tool, tool_in_front) = match &split.tool {
+            SplitTool::Plane(plane_id) => {
+                let plane = &realized
+                    .planes
+                    .get(plane_id)
+                    .ok_or(CADmiumError::PlaneNotFound(plane_id.clone()))?
+                    .plane;
+                let origin = truck_point(&plane.origin);
+                let normal = truck_vector(&plane.tertiary);
+                let primary = truck_vector(&plane.primary);
+                let secondary = normal.cross(primary);
+                let reach = 2.0 * (size + (center - origin).magnitude());
+
+                // a square on the plane, centered right under the solid
+                let middle = center - normal * normal.dot(center - origin);
+                let corners: Vec<TruckPoint3> =
+                    [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+                        .iter()
+                        .map(|(u, v)| middle + primary * (u * reach) + secondary * (v * reach))
+                        .collect();
+                let prism = polygon_prism(&corners, normal * reach)
+                    .ok_or(CADmiumError::GeometryConstructionFailed(name.clone()))?;
+                (prism, true)
+            }
+            SplitTool::Chain { sketch_id, segment } => {
+                let (sketch, split_sketch, _name) = realized
+                    .sketches
+                    .get(sketch_id)
+                    .ok_or(CADmiumError::ChainNotFound(sketch_id.clone()))?;
+                let plane = realized
+                    .planes
+                    .get(&split_sketch.plane_id)
+                    .ok_or(CADmiumError::PlaneNotFound(split_sketch.plane_id.clone()))?;
+
+                // the segment is one of the sketch as it was drawn, which gets a new id
+                // once the sketch is split where it crosses itself
+                let chain = sketch
+                    .split_piece(split_sketch, segment)
+                    .and_then(|piece| split_sketch.chain_through(&piece))
+                    .ok_or(CADmiumError::ChainNotFound(sketch_id.clone()))?;
+                let start = truck_point(&split_sketch.points[&chain[0].get_start()]);
+                let reach = 2.0 * (size + (center - start).magnitude());
+
+                // a wall that runs far past both ends of the chain and reaches far out
+                // to the outside of its bends, pushed back so that it stands out to both
+                // sides of the sketch. Grown on the inside of a bend, it would fold over
+                let turning = chain_turning(plane, split_sketch, chain)
+                    .ok_or(CADmiumError::SplitChainBends(sketch_id.clone()))?;
+                let grows_left = turning <= 0.0;
+                let side = if grows_left {
+                    WallSide::OneSide
+                } else {
+                    WallSide::OtherSide
+                };
+                let extended = extend_chain(plane, split_sketch, chain, reach);
+                let wall = ThinWall::new(vec![], reach, side);
+                let face = Self::thin_face(&name, plane, &extended, chain, &wall)?;
+                let normal = truck_vector(&plane.plane.tertiary) * reach;
+                let face = match face.oriented_surface() {
+                    Surface::Plane(p) if p.normal().dot(normal) < 0.0 => face.inverse(),
+                    _ => face,
+                };
+                (
+                    builder::tsweep(&translated(&face, -normal), normal * 2.0),
+                    grows_left,
+                )
+            }
+        };
+
+        let outside = subtract(&solid.truck_solid, &tool)
+            .ok_or(CADmiumError::BooleanFailed(solid.name.clone()))?;
+        let inside = intersect(&solid.truck_solid, &tool)
+            .ok_or(CADmiumError::BooleanFailed(solid.name.clone()))?;
+        let (front, back) = if tool_in_front {
+            (inside, outside)
+        } else {
+            (outside, inside)
+        };
+        if front.boundaries().is_empty() || back.boundaries().is_empty() {
+            return Err(CADmiumError::SplitMissesSolid(solid.name.clone()));
+        }
+
+        let mut retval = HashMap::new();
+        let pieces = [
+            (front, !matches!(split.keep, SplitKeep::Back)),
+            (back, !matches!(split.keep, SplitKeep::Front)),
+        ];
+        for (index, (piece, kept)) in pieces.into_iter().enumerate() {
+            if kept {
+                let piece_name = format!("{}:{}", name, index);
+                retval.insert(
+                    piece_name.clone(),
+                    Solid::from_truck_solid(piece_name, piece),
+                );
+            }
+        }
+        Ok(retval)
+    }
+
+    // Cuts each tool flagged true out of the solid, and adds the others to it. Edge
+    // features cut on convex edges and fill in concave ones
+    fn apply_tools(
+        solid: &Solid,
+        tools: Vec<(
+            bool,
+            TruckSolid<TruckPoint3, truck_modeling::Curve, Surface>,
+        )>,
+    ) -> Result<Self, CADmiumError> {
+        let mut truck_solid = solid.truck_solid.clone();
+        for (cut, tool) in tools {
+            let result = if cut {
+                subtract(&truck_solid, &tool)
+            } else {
+                // an added tool lies flat against the faces it fills in between, and only
+                // fuse copes with solids that press together like that
+                fuse(&truck_solid, &tool).and_then(|mut fused| match fused.len() {
+                    1 => fused.pop(),
+                    _ => None,
+                })
+            };
+
+            truck_solid = result.ok_or(CADmiumError::BooleanFailed(solid.name.clone()))?;
+        }
+
+        Ok(solid.with_truck_solid(truck_solid))
+    }
+
+    pub fn from_holes(
+        solid: &Solid,
+        hole: &Hole,
+        callouts: &Vec<HoleCallout>,
+    ) -> Result<Self, CADmiumError> {
+        let mut truck_solid = solid.truck_solid.clone();
+        for callout in callouts {
+            let depth = match callout.depth {
+                Some(depth) => depth,
+                None => {
+                    // through all: go one diameter past the furthest point of the solid
+                    let deepest = solid
+                        .vertices
+                        .iter()
+                        .map(|v| v.to_point3().minus(&callout.center).dot(&callout.direction))
+                        .fold(0.0, f64::max);
+                    deepest + callout.diameter
+                }
+            };
+
+            let tool = hole_tool(callout, &hole.profile, depth)
+                .ok_or(CADmiumError::InvalidHoleProfile(solid.name.clone()))?;
+            truck_solid = subtract(&truck_solid, &tool)
+                .ok_or(CADmiumError::BooleanFailed(solid.name.clone()))?;
+        }
+
+        let mut drilled = solid.with_truck_solid(truck_solid);
+        drilled.holes.extend(callouts.iter().cloned());
+        Ok(drilled)
+    }
+
     pub fn from_extrusion(
         name: String,
         plane: &RealPlane,
@@ -141,61 +670,481 @@ impl Solid {
     ) -> HashMap<String, Self> {
         let mut retval = HashMap::new();
 
-        let extrusion_direction = match &extrusion.direction {
-            Direction::Normal => plane.plane.tertiary.clone(),
-            Direction::NegativeNormal => plane.plane.tertiary.times(-1.0),
-            Direction::Specified(vector) => vector.clone(),
+        let extrusion_direction = extrusion.direction_vector(plane);
+
+        // a two sided extrusion also reaches back behind the sketch plane
+        let start = if extrusion.second_length > 0.0 {
+            -extrusion.second_length
+        } else {
+            extrusion.offset
         };
+        let end = extrusion.length;
 
-        let extrusion_vector = extrusion_direction.times(extrusion.length - extrusion.offset);
-        let offset_vector = extrusion_direction.times(extrusion.offset);
+        let extrusion_vector = extrusion_direction.times(end - start);
+        let offset_vector = extrusion_direction.times(start);
 
         let vector = TruckVector3::new(extrusion_vector.x, extrusion_vector.y, extrusion_vector.z);
         let offset_vector = TruckVector3::new(offset_vector.x, offset_vector.y, offset_vector.z);
 
+        // the walls lean in the further they get from the unchanged profile, which sits
+        // on the sketch plane if the extrusion straddles it and at the start otherwise.
+        // Lengths are multiples of the direction, which may not be of unit length, so
+        // the stations are measured by the distance actually covered
+        let travel = extrusion_direction.dot(&extrusion_direction).sqrt();
+        let unit_direction = extrusion_direction.times(1.0 / travel);
+        let (start_travel, end_travel) = (start * travel, end * travel);
+        let slope = extrusion.draft_angle.tan();
+        let stations = if start < 0.0 && end > 0.0 {
+            vec![
+                (start_travel, -start_travel * slope),
+                (0.0, 0.0),
+                (end_travel, end_travel * slope),
+            ]
+        } else {
+            vec![
+                (start_travel, 0.0),
+                (end_travel, (end_travel - start_travel) * slope),
+            ]
+        };
+
+        let faces = Self::selected_faces(sketch, &extrusion.face_ids);
+        for (f_index, face) in faces.iter().enumerate() {
+            let truck_solid = if extrusion.draft_angle == 0.0 {
+                let truck_solid = builder::tsweep(&Self::to_face(plane, sketch, face), vector);
+                translated(&truck_solid, offset_vector)
+            } else {
+                match Self::tapered_sweep(&name, plane, sketch, face, &unit_direction, &stations) {
+                    Ok(truck_solid) => truck_solid,
+                    Err(e) => {
+                        println!("Could not taper face {} of {}: {}", f_index, name, e);
+                        continue;
+                    }
+                }
+            };
+
+            let solid = Solid::from_truck_solid(format!("{}:{}", name, f_index), truck_solid);
+
+            retval.insert(format!("{}:{}", name, f_index), solid);
+        }
+
+        // thin walls are always extruded straight, whatever the draft angle
+        if let Some(thin) = &extrusion.thin {
+            for (c_index, segment) in thin.segments.iter().enumerate() {
+                let face = match sketch
+                    .chain_through(segment)
+                    .ok_or(CADmiumError::ChainNotFound(extrusion.sketch_id.clone()))
+                    .and_then(|chain| Self::thin_face(&name, plane, sketch, chain, thin))
+                {
+                    Ok(face) => face,
+                    Err(e) => {
+                        println!("Could not thicken chain {:?} of {}: {}", segment, name, e);
+                        continue;
+                    }
+                };
+
+                // tsweep wants the face normal to point along the sweep direction
+                let face = match face.oriented_surface() {
+                    Surface::Plane(p) if p.normal().dot(vector) < 0.0 => face.inverse(),
+                    _ => face,
+                };
+                let truck_solid = translated(&builder::tsweep(&face, vector), offset_vector);
+
+                let index = faces.len() + c_index;
+                let solid = Solid::from_truck_solid(format!("{}:{}", name, index), truck_solid);
+
+                retval.insert(format!("{}:{}", name, index), solid);
+            }
+        }
+
+        retval
+    }
+
+    pub fn from_revolve(
+        name: String,
+        plane: &RealPlane,
+        sketch: &RealSketch,
+        revolve: &Revolve,
+        axis_origin: &Point3,
+        axis_direction: &Vector3,
+    ) -> HashMap<String, Self> {
+        let mut retval = HashMap::new();
+
+        let origin = TruckPoint3::new(axis_origin.x, axis_origin.y, axis_origin.z);
+        let axis = TruckVector3::new(axis_direction.x, axis_direction.y, axis_direction.z);
+
+        // rsweep switches to a closed sweep on its own once the angle reaches a full turn
+        let angle = revolve.angle.min(2.0 * PI);
+
+        for (f_index, face) in Self::to_faces(plane, sketch, &revolve.face_ids)
+            .iter()
+            .enumerate()
+        {
+            let truck_solid = builder::rsweep(face, origin, axis, Rad(angle));
+
+            let solid = Solid::from_truck_solid(format!("{}:{}", name, f_index), truck_solid);
+
+            retval.insert(format!("{}:{}", name, f_index), solid);
+        }
+
+        retval
+    }
+
+    // Sweeps each selected face along the path one piece at a time, straight pieces by
+    // translating and arcs by rotating. Each piece starts from the wires the piece
+    // before it ended on, so the pieces share their edges and make one closed shell
+    pub fn from_sweep(
+        name: String,
+        plane: &RealPlane,
+        sketch: &RealSketch,
+        sweep: &Sweep,
+        path: &[PathPiece],
+    ) -> Result<HashMap<String, Self>, CADmiumError> {
+        let mut retval = HashMap::new();
+        let failed = || CADmiumError::GeometryConstructionFailed(name.clone());
+
+        for (f_index, face) in Self::to_faces(plane, sketch, &sweep.face_ids)
+            .iter()
+            .enumerate()
+        {
+            let mut profile = face.clone();
+            let mut previous_tangent: Option<TruckVector3> = None;
+            let mut leading: Option<(TruckFace, bool)> = None;
+            let mut wires: Vec<Wire> = vec![];
+            let mut sides: Vec<TruckFace> = vec![];
+
+            for piece in path {
+                let tangent = piece.start_tangent();
+                match (&sweep.orientation, piece, previous_tangent) {
+                    (SweepOrientation::Fixed, PathPiece::Turn { .. }, _) => {
+                        return Err(CADmiumError::UnsupportedShape(name.clone()));
+                    }
+                    (SweepOrientation::FollowPath, _, Some(previous))
+                        if (previous - tangent).magnitude() > 1e-6 =>
+                    {
+                        return Err(CADmiumError::UnsupportedShape(name.clone()));
+                    }
+                    _ => {}
+                }
+
+                // like tsweep, rsweep wants the face normal to point the way it moves, and
+                // every piece has to carry the profile through the same side
+                let normal = match profile.oriented_surface() {
+                    Surface::Plane(p) => p.normal(),
+                    _ => return Err(CADmiumError::UnsupportedShape(name.clone())),
+                };
+                if normal.dot(tangent).abs() < 1e-6 {
+                    return Err(failed());
+                }
+                let forward = normal.dot(tangent) > 0.0;
+                match &leading {
+                    None => {
+                        let first = if forward {
+                            profile.clone()
+                        } else {
+                            profile.inverse()
+                        };
+                        wires = first.boundaries();
+                        leading = Some((first, forward));
+                    }
+                    Some((_, first_forward)) if *first_forward != forward => {
+                        return Err(failed());
+                    }
+                    _ => {}
+                }
+
+                // a full turn comes back around onto the profile, so there is nothing
+                // left to cap and nowhere for another piece to go
+                let full_turn =
+                    matches!(piece, PathPiece::Turn { angle, .. } if *angle >= 2.0 * PI - 1e-9);
+                if full_turn && path.len() > 1 {
+                    return Err(CADmiumError::UnsupportedShape(name.clone()));
+                }
+
+                let mut next_wires = vec![];
+                for wire in wires.iter() {
+                    let shell = match piece {
+                        PathPiece::Straight { start, end } => builder::tsweep(wire, end - start),
+                        PathPiece::Turn {
+                            center,
+                            axis,
+                            angle,
+                            ..
+                        } => builder::rsweep(wire, *center, *axis, Rad(*angle)),
+                    };
+                    if full_turn {
+                        next_wires.push(wire.clone());
+                    } else {
+                        next_wires.push(carried_wire(wire, &shell, piece).ok_or(failed())?);
+                    }
+                    sides.extend(shell.face_iter().cloned());
+                }
+                wires = next_wires;
+
+                profile = match piece {
+                    PathPiece::Straight { start, end } => translated(&profile, end - start),
+                    PathPiece::Turn {
+                        center,
+                        axis,
+                        angle,
+                        ..
+                    } => builder::rotated(&profile, *center, *axis, Rad(*angle)),
+                };
+                previous_tangent = Some(piece.end_tangent());
+            }
+
+            let (leading, forward) = leading.ok_or(failed())?;
+            let mut shell = TruckShell::new();
+            for side in sides {
+                shell.push(side);
+            }
+            let closed =
+                matches!(path, [PathPiece::Turn { angle, .. }] if *angle >= 2.0 * PI - 1e-9);
+            if !closed {
+                let trailing_surface = if forward {
+                    profile.oriented_surface()
+                } else {
+                    profile.inverse().oriented_surface()
+                };
+                let trailing = TruckFace::try_new(wires, trailing_surface).map_err(|_| failed())?;
+                shell.push(leading.inverse());
+                shell.push(trailing);
+            }
+            let truck_solid = TruckSolid::try_new(vec![shell]).map_err(|_| failed())?;
+            let solid = Solid::from_truck_solid(format!("{}:{}", name, f_index), truck_solid);
+
+            retval.insert(format!("{}:{}", name, f_index), solid);
+        }
+
+        Ok(retval)
+    }
+
+    // Joins the exterior of one face from each profile sketch into a single solid. Each
+    // profile comes with the plane and split sketch it was drawn in
+    pub fn from_loft(
+        name: String,
+        profiles: &[(&RealPlane, &RealSketch)],
+        loft: &Loft,
+    ) -> Result<HashMap<String, Self>, CADmiumError> {
+        if loft.profiles.len() < 2 || profiles.len() != loft.profiles.len() {
+            return Err(CADmiumError::GeometryConstructionFailed(name));
+        }
+
+        let mut outlines = vec![];
+        for ((plane, sketch), profile) in profiles.iter().zip(loft.profiles.iter()) {
+            outlines.push(outline(plane, sketch, profile)?);
+        }
+        let fixed_starts: Vec<bool> = loft
+            .profiles
+            .iter()
+            .map(|profile| profile.start_point.is_some())
+            .collect();
+
+        let truck_solid = loft_solid(&line_up(outlines, &fixed_starts))
+            .ok_or(CADmiumError::GeometryConstructionFailed(name.clone()))?;
+
+        let mut retval = HashMap::new();
+        let solid = Solid::from_truck_solid(format!("{}:0", name), truck_solid);
+        retval.insert(format!("{}:0", name), solid);
+        Ok(retval)
+    }
+
+    pub fn to_faces(plane: &RealPlane, sketch: &RealSketch, face_ids: &Vec<u64>) -> Vec<TruckFace> {
+        Self::selected_faces(sketch, face_ids)
+            .iter()
+            .map(|face| Self::to_face(plane, sketch, face))
+            .collect()
+    }
+
+    fn selected_faces(sketch: &RealSketch, face_ids: &Vec<u64>) -> Vec<Face> {
         // Sometimes the chosen faces are touching, or one even envelops another. Let's
         // merge those faces together so that we have single solid wherever possible
-        let unmerged_faces: Vec<Face> = extrusion
-            .face_ids
+        let unmerged_faces: Vec<Face> = face_ids
             .iter()
             .map(|face_id| sketch.faces.get(*face_id as usize).unwrap().clone())
             .collect();
-        let merged_faces = merge_faces(&unmerged_faces, sketch);
+        merge_faces(&unmerged_faces, sketch)
+    }
 
-        for (f_index, face) in merged_faces.iter().enumerate() {
-            // let face = sketch.faces.get(*face_id as usize).unwrap();
+    pub fn to_face(plane: &RealPlane, sketch: &RealSketch, face: &Face) -> TruckFace {
+        let mut wires: Vec<Wire> = Vec::new();
 
-            // println!("face: {:?}", face);
-            let exterior = &face.exterior;
-            let mut wires: Vec<Wire> = Vec::new();
+        // the exterior wire comes first
+        wires.push(Self::to_wire(plane, sketch, &face.exterior));
 
-            // the exterior wire comes first
-            wires.push(Self::to_wire(plane, sketch, extrusion, exterior));
+        // then the interior wires
+        for interior in &face.holes {
+            wires.push(Self::to_wire(plane, sketch, interior).inverse());
+        }
 
-            // then the interior wires
-            for interior in &face.holes {
-                wires.push(Self::to_wire(plane, sketch, extrusion, interior).inverse());
-            }
+        builder::try_attach_plane(&wires).unwrap()
+    }
 
-            let face = builder::try_attach_plane(&wires).unwrap();
+    // The outline of a wall grown out of an open chain, running along one side of the
+    // chain and back along the other
+    fn thin_face(
+        name: &str,
+        plane: &RealPlane,
+        sketch: &RealSketch,
+        chain: &[Segment],
+        thin: &ThinWall,
+    ) -> Result<TruckFace, CADmiumError> {
+        if thin.thickness <= 0.0 || chain.is_empty() {
+            return Err(CADmiumError::GeometryConstructionFailed(name.to_owned()));
+        }
 
-            let truck_solid = builder::tsweep(&face, vector);
-            let truck_solid = translated(&truck_solid, offset_vector);
+        let (left, right) = thin.offsets();
+        let mut vertices: Vec<Vec<Vertex>> = vec![];
+        for distance in [left, right] {
+            let moved = offset_chain(chain, &sketch.points_2d, distance)
+                .ok_or(CADmiumError::WallTooThick(name.to_owned()))?;
+            vertices.push(
+                moved
+                    .iter()
+                    .map(|p| {
+                        let p = plane.plane.unproject(p);
+                        builder::vertex(TruckPoint3::new(p.x, p.y, p.z))
+                    })
+                    .collect(),
+            );
+        }
 
-            let solid = Solid::from_truck_solid(format!("{}:{}", name, f_index), truck_solid);
+        // the edge along one side of the wall that follows the i-th segment of the chain
+        let along = |side: usize, i: usize| -> Edge {
+            let (start, end) = (&vertices[side][i], &vertices[side][i + 1]);
+            match &chain[i] {
+                Segment::Line(_) => builder::line(start, end),
+                Segment::Arc(arc) => {
+                    // the moved arc's middle is straight out from the center through the
+                    // middle of the original arc
+                    let center = &sketch.points[&arc.center];
+                    let transit = find_transit(
+                        plane,
+                        &sketch.points[&arc.start],
+                        &sketch.points[&arc.end],
+                        center,
+                        arc.clockwise,
+                    );
+                    let center = TruckPoint3::new(center.x, center.y, center.z);
+                    let out =
+                        (TruckPoint3::new(transit.x, transit.y, transit.z) - center).normalize();
+                    let radius = (start.point() - center).magnitude();
+                    builder::circle_arc(start, end, center + out * radius)
+                }
+            }
+        };
 
-            retval.insert(format!("{}:{}", name, f_index), solid);
+        let last = chain.len();
+        let mut edges = vec![];
+        for i in 0..last {
+            edges.push(along(0, i));
         }
+        edges.push(builder::line(&vertices[0][last], &vertices[1][last]));
+        for i in (0..last).rev() {
+            edges.push(along(1, i).inverse());
+        }
+        edges.push(builder::line(&vertices[1][0], &vertices[0][0]));
 
-        retval
+        builder::try_attach_plane(&[edges.into_iter().collect::<Wire>()])
+            .map_err(|_| CADmiumError::GeometryConstructionFailed(name.to_owned()))
     }
 
-    pub fn to_wire(
+    // Extrudes the face along the unit direction, through a series of (distance, inset)
+    // stations. At each station the outline is pulled in by inset, so the side walls lean
+    // in (positive inset) or out (negative inset) between them.
+    fn tapered_sweep(
+        name: &str,
         plane: &RealPlane,
         sketch: &RealSketch,
-        _extrusion: &Extrusion,
-        exterior: &Ring,
-    ) -> Wire {
+        face: &Face,
+        direction: &Vector3,
+        stations: &[(f64, f64)],
+    ) -> Result<truck_modeling::Solid, CADmiumError> {
+        let failed = || CADmiumError::GeometryConstructionFailed(name.to_owned());
+
+        // put every ring in counterclockwise order so that "left" is always toward the
+        // inside of the ring
+        let counterclockwise = |ring: &Ring| {
+            if ring_is_counterclockwise(ring, &sketch.points_2d) {
+                ring.clone()
+            } else {
+                ring.reverse()
+            }
+        };
+        let exterior = counterclockwise(&face.exterior);
+        let holes: Vec<Ring> = face.holes.iter().map(counterclockwise).collect();
+
+        // pulled in too far, the outline shrinks down past nothing and turns inside out
+        for (_, inset) in stations {
+            if !offset_keeps_ring(&exterior, &sketch.points_2d, *inset)
+                || holes
+                    .iter()
+                    .any(|hole| !offset_keeps_ring(hole, &sketch.points_2d, -*inset))
+            {
+                return Err(CADmiumError::DraftCollapsesProfile(name.to_owned()));
+            }
+        }
+
+        // the outline at each station is a copy of the sketch, moved along direction
+        // and with the rings offset. Material is on the left of the exterior but on the
+        // right of the holes, so holes get offset the other way
+        let outline = |distance: f64, inset: f64| {
+            let vector = direction.times(distance);
+            let mut station_plane = plane.clone();
+            station_plane.plane.origin = plane.plane.origin.plus(vector.clone()).to_point3();
+
+            let mut station_sketch = sketch.clone();
+            for point in station_sketch.points.values_mut() {
+                *point = point.plus(vector.clone()).to_point3();
+            }
+
+            let mut moves = offset_ring(&exterior, &sketch.points_2d, inset);
+            for hole in &holes {
+                moves.extend(offset_ring(hole, &sketch.points_2d, -inset));
+            }
+            for (point_id, moved) in moves {
+                let original = &sketch.points_2d[&point_id];
+                let shift = plane
+                    .plane
+                    .primary
+                    .times(moved.x - original.x)
+                    .plus(plane.plane.secondary.times(moved.y - original.y));
+                let shifted = station_sketch.points[&point_id].plus(shift).to_point3();
+                station_sketch.points.insert(point_id, shifted);
+            }
+
+            let mut wires = vec![Self::to_wire(&station_plane, &station_sketch, &exterior)];
+            for hole in &holes {
+                wires.push(Self::to_wire(&station_plane, &station_sketch, hole).inverse());
+            }
+            wires
+        };
+
+        let outlines: Vec<Vec<Wire>> = stations
+            .iter()
+            .map(|(distance, inset)| outline(*distance, *inset))
+            .collect();
+
+        let mut shell: Vec<TruckFace> = vec![];
+        for pair in outlines.windows(2) {
+            for (near, far) in pair[0].iter().zip(pair[1].iter()) {
+                shell.extend(builder::wire_homotopy(near, far).face_iter().cloned());
+            }
+        }
+        let cap = |outline: Option<&Vec<Wire>>| {
+            builder::try_attach_plane(outline.ok_or_else(failed)?).map_err(|_| failed())
+        };
+        shell.push(cap(outlines.first())?.inverse());
+        shell.push(cap(outlines.last())?);
+
+        // the faces above all point outward when sweeping along the plane's normal
+        if direction.dot(&plane.plane.tertiary) < 0.0 {
+            shell = shell.iter().map(|face| face.inverse()).collect();
+        }
+
+        truck_modeling::Solid::try_new(vec![shell.into_iter().collect()]).map_err(|_| failed())
+    }
+
+    pub fn to_wire(plane: &RealPlane, sketch: &RealSketch, exterior: &Ring) -> Wire {
         match exterior {
             Ring::Circle(circle) => {
                 println!("circle: {:?}", circle);
@@ -324,3 +1273,135 @@ impl Solid {
         std::io::Write::write_all(&mut step_file, step_text.as_ref()).unwrap();
     }
 }
+
+// Sorts the shells of a solid into separate bodies, largest first. Shells that enclose
+// negative volume are voids and stay with the body around them.
+pub fn split_shells(
+    solid: &TruckSolid<TruckPoint3, truck_modeling::Curve, Surface>,
+) -> Vec<TruckSolid<TruckPoint3, truck_modeling::Curve, Surface>> {
+    let shells = solid.boundaries();
+    if shells.len() == 1 {
+        return vec![solid.clone()];
+    }
+
+    let volumes: Vec<f64> = shells.iter().map(shell_volume).collect();
+    let extents: Vec<(TruckPoint3, TruckPoint3)> = shells
+        .iter()
+        .map(|shell| points_extent(shell.vertex_iter().map(|v| v.point())))
+        .collect();
+
+    let mut bodies: Vec<(f64, Vec<usize>)> = (0..shells.len())
+        .filter(|i| volumes[*i] > 0.0)
+        .map(|i| (volumes[i], vec![i]))
+        .collect();
+    bodies.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    for void in (0..shells.len()).filter(|i| volumes[*i] <= 0.0) {
+        let (low, high) = extents[void];
+        // the void belongs to the smallest body whose extent covers it
+        let owner = bodies
+            .iter_mut()
+            .filter(|(_, members)| {
+                let (outer_low, outer_high) = extents[members[0]];
+                outer_low.x <= low.x
+                    && outer_low.y <= low.y
+                    && outer_low.z <= low.z
+                    && outer_high.x >= high.x
+                    && outer_high.y >= high.y
+                    && outer_high.z >= high.z
+            })
+            .last();
+        match owner {
+            Some((_, members)) => members.push(void),
+            None => println!("Found a void outside of every body"),
+        }
+    }
+
+    bodies
+        .into_iter()
+        .map(|(_, members)| TruckSolid::new(members.iter().map(|i| shells[*i].clone()).collect()))
+        .collect()
+}
+
+// The wire a swept wire ends on. Sweeping a wire gives one side face per edge, in the
+// wire's order, and the carried copy of each edge is the one whose ends are the
+// original ends moved along the piece
+fn carried_wire(wire: &Wire, swept: &TruckShell, piece: &PathPiece) -> Option<Wire> {
+    let near = |a: TruckPoint3, b: TruckPoint3| (a - b).magnitude() < 1e-6;
+    let mut edges = vec![];
+    for (edge, side) in wire.edge_iter().zip(swept.face_iter()) {
+        let front = piece.carry(edge.front().point());
+        let back = piece.carry(edge.back().point());
+        let carried = side.boundary_iters().into_iter().flatten().find_map(|e| {
+            if near(e.front().point(), front) && near(e.back().point(), back) {
+                Some(e)
+            } else if near(e.front().point(), back) && near(e.back().point(), front) {
+                Some(e.inverse())
+            } else {
+                None
+            }
+        })?;
+        edges.push(carried);
+    }
+    Some(Wire::from_iter(edges))
+}
+
+// The volume enclosed by a closed shell: positive if its faces point outward, negative
+// if it is the inside-out boundary of a void
+fn shell_volume(shell: &truck_modeling::Shell) -> f64 {
+    let mesh = shell.triangulation(0.01).to_polygon();
+    let positions = mesh.positions();
+    mesh.tri_faces()
+        .iter()
+        .map(|triangle| {
+            let a = positions[triangle[0].pos] - TruckPoint3::new(0.0, 0.0, 0.0);
+            let b = positions[triangle[1].pos] - TruckPoint3::new(0.0, 0.0, 0.0);
+            let c = positions[triangle[2].pos] - TruckPoint3::new(0.0, 0.0, 0.0);
+            a.dot(b.cross(c)) / 6.0
+        })
+        .sum()
+}
+
+// Whether a planar face lying in the sketch's plane covers a point of the sketch. Each
+// boundary of the face is traced out in the sketch's coordinates, and the point is
+// inside the face if it is inside an odd number of them
+fn plane_face_contains(face: &TruckFace, plane: &RealPlane, point: &Point2) -> bool {
+    let normal = TruckVector3::new(
+        plane.plane.tertiary.x,
+        plane.plane.tertiary.y,
+        plane.plane.tertiary.z,
+    )
+    .normalize();
+    let origin = TruckPoint3::new(
+        plane.plane.origin.x,
+        plane.plane.origin.y,
+        plane.plane.origin.z,
+    );
+    match face.oriented_surface() {
+        Surface::Plane(p)
+            if p.normal().cross(normal).magnitude() < 1e-6
+                && (p.origin() - origin).dot(normal).abs() < 1e-6 => {}
+        _ => return false,
+    }
+
+    let samples = 16;
+    let crossings = face
+        .boundaries()
+        .iter()
+        .filter(|wire| {
+            let mut outline: Vec<(f64, f64)> = vec![];
+            for edge in wire.edge_iter() {
+                let curve = edge.oriented_curve();
+                let (start, end) = curve.range_tuple();
+                for i in 0..samples {
+                    let p = curve.subs(start + (end - start) * i as f64 / samples as f64);
+                    let p = plane.plane.project(&Point3::new(p.x, p.y, p.z));
+                    outline.push((p.x, p.y));
+                }
+            }
+            geo::Polygon::new(geo::LineString::from(outline), vec![])
+                .contains(&geo::Point::new(point.x, point.y))
+        })
+        .count();
+    crossings % 2 == 1
+}
@@ -0,0 +1,91 @@
+use std::f64::consts::PI;
+
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use crate::extrusion::left_at;
+use crate::project::{RealPlane, RealSketch};
+use crate::sketch::Segment;
+use crate::sweep::{arc_angle, PathSegment};
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum SplitTool {
+    // a plane from the realization. The front side is the one its normal points to
+    Plane(String),
+    // the open chain of a sketch through a line or arc, extruded both ways along the
+    // sketch's normal into a surface. The front side is to the left of the chain, looking
+    // along it from its start. The chain can bend only one way, and by no more than a half
+    // turn in all
+    Chain {
+        sketch_id: String,
+        segment: PathSegment,
+    },
+}
+
+// Which of the two pieces of a split solid are kept
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum SplitKeep {
+    Both,
+    Front,
+    Back,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct Split {
+    pub solid_id: String,
+    pub tool: SplitTool,
+    pub keep: SplitKeep,
+}
+
+impl Split {
+    pub fn new(solid_id: String, tool: SplitTool, keep: SplitKeep) -> Self {
+        Split {
+            solid_id,
+            tool,
+            keep,
+        }
+    }
+}
+
+// How far a chain turns to the left from its start to its end, in radians, counting both
+// its arcs and the corners between its segments. None if it bends to both sides or
+// turns back on itself by more than a half turn
+pub fn chain_turning(plane: &RealPlane, sketch: &RealSketch, chain: &[Segment]) -> Option<f64> {
+    // looking along a segment, the way ahead is a quarter turn clockwise from its left
+    let heading = |segment: &Segment, point_id: u64| {
+        let (nx, ny) = left_at(segment, &sketch.points_2d[&point_id], &sketch.points_2d);
+        (ny, -nx)
+    };
+
+    let mut turns = vec![];
+    for segment in chain {
+        if let Segment::Arc(arc) = segment {
+            let angle = arc_angle(
+                plane,
+                &sketch.points[&arc.start],
+                &sketch.points[&arc.end],
+                &sketch.points[&arc.center],
+                arc.clockwise,
+            );
+            turns.push(if arc.clockwise { -angle } else { angle });
+        }
+    }
+    for pair in chain.windows(2) {
+        let corner = pair[0].get_end();
+        let (ax, ay) = heading(&pair[0], corner);
+        let (bx, by) = heading(&pair[1], corner);
+        turns.push((ax * by - ay * bx).atan2(ax * bx + ay * by));
+    }
+
+    let left = turns.iter().any(|turn| *turn > 1e-9);
+    let right = turns.iter().any(|turn| *turn < -1e-9);
+    let total: f64 = turns.iter().sum();
+    if (left && right) || total.abs() > PI + 1e-9 {
+        None
+    } else {
+        Some(total)
+    }
+}
@@ -3,9 +3,27 @@ use serde::{Deserialize, Serialize};
 use tsify::Tsify;
 use wasm_bindgen::prelude::*;
 
-use crate::archetypes::{Plane, PlaneDescription, Point3, Vector3};
+use crate::archetypes::{Plane, Point3, Vector3};
+use crate::axis::Axis;
 use crate::sketch::Sketch;
+use crate::boolean::Boolean;
+use crate::chamfer::Chamfer;
+use crate::draft::Draft;
 use crate::extrusion::Extrusion;
+use crate::fillet::Fillet;
+use crate::hole::Hole;
+use crate::loft::Loft;
+use crate::mirror::Mirror;
+use crate::offset_face::OffsetFace;
+use crate::pattern::{CircularPattern, LinearPattern};
+use crate::point::PointDescription;
+use crate::realization::PlaneDescription;
+use crate::revolve::Revolve;
+use crate::rib::Rib;
+use crate::shell::Shell;
+use crate::split::Split;
+use crate::sweep::Sweep;
+use crate::transform::Transform;
 
 #[derive(Tsify, Debug, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -14,11 +32,23 @@ pub enum StepData {
     Point {
         point: Point3,
     },
+    DerivedPoint {
+        point: PointDescription,
+    },
     Plane {
         plane: Plane,
         width: f64,
         height: f64,
     },
+    // a plane worked out from other geometry every time the workbench is realized
+    DerivedPlane {
+        plane: PlaneDescription,
+        width: f64,
+        height: f64,
+    },
+    Axis {
+        axis: Axis,
+    },
     Sketch {
         plane_description: PlaneDescription,
         width: f64,
@@ -28,6 +58,54 @@ pub enum StepData {
     Extrusion {
         extrusion: Extrusion,
     },
+    Revolve {
+        revolve: Revolve,
+    },
+    Fillet {
+        fillet: Fillet,
+    },
+    Chamfer {
+        chamfer: Chamfer,
+    },
+    Hole {
+        hole: Hole,
+    },
+    Draft {
+        draft: Draft,
+    },
+    Shell {
+        shell: Shell,
+    },
+    LinearPattern {
+        linear_pattern: LinearPattern,
+    },
+    CircularPattern {
+        circular_pattern: CircularPattern,
+    },
+    Mirror {
+        mirror: Mirror,
+    },
+    Sweep {
+        sweep: Sweep,
+    },
+    Loft {
+        loft: Loft,
+    },
+    Rib {
+        rib: Rib,
+    },
+    Split {
+        split: Split,
+    },
+    Boolean {
+        boolean: Boolean,
+    },
+    Transform {
+        transform: Transform,
+    },
+    OffsetFace {
+        offset_face: OffsetFace,
+    },
 }
 
 #[derive(Tsify, Debug, Serialize, Deserialize)]
@@ -51,6 +129,15 @@ impl Step {
         }
     }
 
+    pub fn new_derived_point(name: &str, point: PointDescription, point_id: u64) -> Self {
+        Step {
+            name: name.to_owned(),
+            unique_id: format!("Point-{}", point_id),
+            suppressed: false,
+            data: StepData::DerivedPoint { point },
+        }
+    }
+
     pub fn new_plane(name: &str, plane: Plane, plane_id: u64) -> Self {
         Step {
             name: name.to_owned(),
@@ -64,6 +151,28 @@ impl Step {
         }
     }
 
+    pub fn new_derived_plane(name: &str, plane: PlaneDescription, plane_id: u64) -> Self {
+        Step {
+            name: name.to_owned(),
+            unique_id: format!("Plane-{}", plane_id),
+            suppressed: false,
+            data: StepData::DerivedPlane {
+                plane,
+                height: 100.0,
+                width: 100.0,
+            },
+        }
+    }
+
+    pub fn new_axis(name: &str, axis: Axis, axis_id: u64) -> Self {
+        Step {
+            name: name.to_owned(),
+            unique_id: format!("Axis-{}", axis_id),
+            suppressed: false,
+            data: StepData::Axis { axis },
+        }
+    }
+
     pub fn new_sketch(name: &str, plane_id: &str, sketch_id: u64) -> Self {
         Step {
             name: name.to_owned(),
@@ -92,6 +201,7 @@ impl Step {
                 plane_description: PlaneDescription::SolidFace {
                     solid_id: solid_id.to_owned(),
                     normal,
+                    near: None,
                 },
                 width: 12.5,
                 height: 7.5,
@@ -100,6 +210,24 @@ impl Step {
         }
     }
 
+    pub fn new_sketch_on_derived_plane(
+        name: &str,
+        plane_description: PlaneDescription,
+        sketch_id: u64,
+    ) -> Self {
+        Step {
+            name: name.to_owned(),
+            unique_id: format!("Sketch-{}", sketch_id),
+            suppressed: false,
+            data: StepData::Sketch {
+                plane_description,
+                width: 12.5,
+                height: 7.5,
+                sketch: Sketch::new(),
+            },
+        }
+    }
+
     pub fn new_extrusion(name: &str, extrusion: Extrusion, extrusion_id: u64) -> Self {
         Step {
             name: name.to_owned(),
@@ -108,4 +236,152 @@ impl Step {
             data: StepData::Extrusion { extrusion },
         }
     }
+
+    pub fn new_revolve(name: &str, revolve: Revolve, revolve_id: u64) -> Self {
+        Step {
+            name: name.to_owned(),
+            unique_id: format!("Revolve-{}", revolve_id),
+            suppressed: false,
+            data: StepData::Revolve { revolve },
+        }
+    }
+
+    pub fn new_fillet(name: &str, fillet: Fillet, fillet_id: u64) -> Self {
+        Step {
+            name: name.to_owned(),
+            unique_id: format!("Fillet-{}", fillet_id),
+            suppressed: false,
+            data: StepData::Fillet { fillet },
+        }
+    }
+
+    pub fn new_chamfer(name: &str, chamfer: Chamfer, chamfer_id: u64) -> Self {
+        Step {
+            name: name.to_owned(),
+            unique_id: format!("Chamfer-{}", chamfer_id),
+            suppressed: false,
+            data: StepData::Chamfer { chamfer },
+        }
+    }
+
+    pub fn new_hole(name: &str, hole: Hole, hole_id: u64) -> Self {
+        Step {
+            name: name.to_owned(),
+            unique_id: format!("Hole-{}", hole_id),
+            suppressed: false,
+            data: StepData::Hole { hole },
+        }
+    }
+
+    pub fn new_draft(name: &str, draft: Draft, draft_id: u64) -> Self {
+        Step {
+            name: name.to_owned(),
+            unique_id: format!("Draft-{}", draft_id),
+            suppressed: false,
+            data: StepData::Draft { draft },
+        }
+    }
+
+    pub fn new_shell(name: &str, shell: Shell, shell_id: u64) -> Self {
+        Step {
+            name: name.to_owned(),
+            unique_id: format!("Shell-{}", shell_id),
+            suppressed: false,
+            data: StepData::Shell { shell },
+        }
+    }
+
+    pub fn new_linear_pattern(name: &str, linear_pattern: LinearPattern, pattern_id: u64) -> Self {
+        Step {
+            name: name.to_owned(),
+            unique_id: format!("LinearPattern-{}", pattern_id),
+            suppressed: false,
+            data: StepData::LinearPattern { linear_pattern },
+        }
+    }
+
+    pub fn new_circular_pattern(
+        name: &str,
+        circular_pattern: CircularPattern,
+        pattern_id: u64,
+    ) -> Self {
+        Step {
+            name: name.to_owned(),
+            unique_id: format!("CircularPattern-{}", pattern_id),
+            suppressed: false,
+            data: StepData::CircularPattern { circular_pattern },
+        }
+    }
+
+    pub fn new_mirror(name: &str, mirror: Mirror, mirror_id: u64) -> Self {
+        Step {
+            name: name.to_owned(),
+            unique_id: format!("Mirror-{}", mirror_id),
+            suppressed: false,
+            data: StepData::Mirror { mirror },
+        }
+    }
+
+    pub fn new_sweep(name: &str, sweep: Sweep, sweep_id: u64) -> Self {
+        Step {
+            name: name.to_owned(),
+            unique_id: format!("Sweep-{}", sweep_id),
+            suppressed: false,
+            data: StepData::Sweep { sweep },
+        }
+    }
+
+    pub fn new_loft(name: &str, loft: Loft, loft_id: u64) -> Self {
+        Step {
+            name: name.to_owned(),
+            unique_id: format!("Loft-{}", loft_id),
+            suppressed: false,
+            data: StepData::Loft { loft },
+        }
+    }
+
+    pub fn new_rib(name: &str, rib: Rib, rib_id: u64) -> Self {
+        Step {
+            name: name.to_owned(),
+            unique_id: format!("Rib-{}", rib_id),
+            suppressed: false,
+            data: StepData::Rib { rib },
+        }
+    }
+
+    pub fn new_split(name: &str, split: Split, split_id: u64) -> Self {
+        Step {
+            name: name.to_owned(),
+            unique_id: format!("Split-{}", split_id),
+            suppressed: false,
+            data: StepData::Split { split },
+        }
+    }
+
+    pub fn new_boolean(name: &str, boolean: Boolean, boolean_id: u64) -> Self {
+        Step {
+            name: name.to_owned(),
+            unique_id: format!("Boolean-{}", boolean_id),
+            suppressed: false,
+            data: StepData::Boolean { boolean },
+        }
+    }
+
+    pub fn new_transform(name: &str, transform: Transform, transform_id: u64) -> Self {
+        Step {
+            name: name.to_owned(),
+            unique_id: format!("Transform-{}", transform_id),
+            suppressed: false,
+            data: StepData::Transform { transform },
+        }
+    }
+
+    pub fn new_offset_face(name: &str, offset_face: OffsetFace, offset_face_id: u64) -> Self {
+        Step {
+            name: name.to_owned(),
+            unique_id: format!("OffsetFace-{}", offset_face_id),
+            suppressed: false,
+            data: StepData::OffsetFace { offset_face },
+        }
+    }
 }
@@ -0,0 +1,246 @@
+use std::f64::consts::PI;
+
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use truck_modeling::{InnerSpace, Matrix3, Point3 as TruckPoint3, Vector3 as TruckVector3};
+use truck_polymesh::Rad;
+
+use crate::archetypes::Point3;
+use crate::error::CADmiumError;
+use crate::extrusion::ExtrusionMode;
+use crate::project::{RealPlane, RealSketch};
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum PathSegment {
+    Line(u64),
+    Arc(u64),
+}
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum SweepOrientation {
+    // the profile turns with the path, keeping the same angle to it all the way along.
+    // The path has to be smooth, so lines can only meet arcs and other lines head on
+    FollowPath,
+    // the profile keeps facing the same way however the path turns. The path can have
+    // sharp corners, but only straight lines
+    Fixed,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct Sweep {
+    pub profile_sketch_id: String,
+    pub face_ids: Vec<u64>,
+    pub path_sketch_id: String,
+    // lines and arcs of the path sketch, in the order they are joined end to end. Each
+    // one may point either way
+    pub path: Vec<PathSegment>,
+    pub orientation: SweepOrientation,
+    pub mode: ExtrusionMode,
+}
+
+impl Sweep {
+    pub fn new(
+        profile_sketch_id: String,
+        face_ids: Vec<u64>,
+        path_sketch_id: String,
+        path: Vec<PathSegment>,
+        orientation: SweepOrientation,
+        mode: ExtrusionMode,
+    ) -> Self {
+        Sweep {
+            profile_sketch_id,
+            face_ids,
+            path_sketch_id,
+            path,
+            orientation,
+            mode,
+        }
+    }
+
+    // Looks up the path in the sketch it was drawn in and lines its pieces up so that
+    // each one starts where the one before it ends
+    pub fn path_pieces(
+        &self,
+        plane: &RealPlane,
+        sketch: &RealSketch,
+    ) -> Result<Vec<PathPiece>, CADmiumError> {
+        let invalid = || CADmiumError::InvalidPath(self.path_sketch_id.clone());
+        let point =
+            |id: &u64| -> Result<&Point3, CADmiumError> { sketch.points.get(id).ok_or(invalid()) };
+        let truck_point = |p: &Point3| TruckPoint3::new(p.x, p.y, p.z);
+        let normal = TruckVector3::new(
+            plane.plane.tertiary.x,
+            plane.plane.tertiary.y,
+            plane.plane.tertiary.z,
+        )
+        .normalize();
+
+        let mut pieces = vec![];
+        for segment in self.path.iter() {
+            let piece = match segment {
+                PathSegment::Line(id) => {
+                    let line = sketch.line_segments.get(id).ok_or(invalid())?;
+                    PathPiece::Straight {
+                        start: truck_point(point(&line.start)?),
+                        end: truck_point(point(&line.end)?),
+                    }
+                }
+                PathSegment::Arc(id) => {
+                    let arc = sketch.arcs.get(id).ok_or(invalid())?;
+                    let (start, end, center) =
+                        (point(&arc.start)?, point(&arc.end)?, point(&arc.center)?);
+
+                    PathPiece::Turn {
+                        start: truck_point(start),
+                        end: truck_point(end),
+                        center: truck_point(center),
+                        axis: if arc.clockwise { -normal } else { normal },
+                        angle: arc_angle(plane, start, end, center, arc.clockwise),
+                    }
+                }
+            };
+            pieces.push(piece);
+        }
+
+        if pieces.is_empty() {
+            return Err(invalid());
+        }
+
+        // the first piece runs away from the second one, and every other piece has to
+        // pick up where the one before it left off
+        let meets = |a: TruckPoint3, b: TruckPoint3| (a - b).magnitude() < 1e-6;
+        if pieces.len() > 1 {
+            let (first_start, _) = pieces[0].ends();
+            let (second_start, second_end) = pieces[1].ends();
+            if meets(first_start, second_start) || meets(first_start, second_end) {
+                pieces[0] = pieces[0].reversed();
+            }
+        }
+        for i in 1..pieces.len() {
+            let (_, previous_end) = pieces[i - 1].ends();
+            let (start, end) = pieces[i].ends();
+            if meets(end, previous_end) && !meets(start, previous_end) {
+                pieces[i] = pieces[i].reversed();
+            } else if !meets(start, previous_end) {
+                return Err(invalid());
+            }
+        }
+
+        Ok(pieces)
+    }
+}
+
+// How far an arc of a sketch turns from its start to its end, measured in the sketch's
+// own coordinates the same way its edges get drawn. An arc that ends where it starts
+// goes all the way around
+pub fn arc_angle(
+    plane: &RealPlane,
+    start: &Point3,
+    end: &Point3,
+    center: &Point3,
+    clockwise: bool,
+) -> f64 {
+    let start_2d = plane.plane.project(start);
+    let end_2d = plane.plane.project(end);
+    let center_2d = plane.plane.project(center);
+    let start_angle = (start_2d.y - center_2d.y).atan2(start_2d.x - center_2d.x);
+    let end_angle = (end_2d.y - center_2d.y).atan2(end_2d.x - center_2d.x);
+    let angle = if clockwise {
+        (start_angle - end_angle).rem_euclid(2.0 * PI)
+    } else {
+        (end_angle - start_angle).rem_euclid(2.0 * PI)
+    };
+    if angle < 1e-9 {
+        2.0 * PI
+    } else {
+        angle
+    }
+}
+
+// One piece of a sweep path in 3D, pointing the way the profile travels along it
+#[derive(Debug, Clone)]
+pub enum PathPiece {
+    Straight {
+        start: TruckPoint3,
+        end: TruckPoint3,
+    },
+    // a turn about the axis through center, following the right hand rule
+    Turn {
+        start: TruckPoint3,
+        end: TruckPoint3,
+        center: TruckPoint3,
+        axis: TruckVector3,
+        angle: f64,
+    },
+}
+
+impl PathPiece {
+    pub fn ends(&self) -> (TruckPoint3, TruckPoint3) {
+        match self {
+            PathPiece::Straight { start, end } => (*start, *end),
+            PathPiece::Turn { start, end, .. } => (*start, *end),
+        }
+    }
+
+    pub fn start_tangent(&self) -> TruckVector3 {
+        match self {
+            PathPiece::Straight { start, end } => (end - start).normalize(),
+            PathPiece::Turn {
+                start,
+                center,
+                axis,
+                ..
+            } => axis.cross(start - center).normalize(),
+        }
+    }
+
+    pub fn end_tangent(&self) -> TruckVector3 {
+        match self {
+            PathPiece::Straight { start, end } => (end - start).normalize(),
+            PathPiece::Turn {
+                end, center, axis, ..
+            } => axis.cross(end - center).normalize(),
+        }
+    }
+
+    // Where a point of the profile ends up once the profile has travelled this piece
+    pub fn carry(&self, point: TruckPoint3) -> TruckPoint3 {
+        match self {
+            PathPiece::Straight { start, end } => point + (end - start),
+            PathPiece::Turn {
+                center,
+                axis,
+                angle,
+                ..
+            } => {
+                center + Matrix3::from_axis_angle(axis.normalize(), Rad(*angle)) * (point - center)
+            }
+        }
+    }
+
+    fn reversed(&self) -> Self {
+        match self {
+            PathPiece::Straight { start, end } => PathPiece::Straight {
+                start: *end,
+                end: *start,
+            },
+            PathPiece::Turn {
+                start,
+                end,
+                center,
+                axis,
+                angle,
+            } => PathPiece::Turn {
+                start: *end,
+                end: *start,
+                center: *center,
+                axis: -*axis,
+                angle: *angle,
+            },
+        }
+    }
+}
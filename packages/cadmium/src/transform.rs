@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use truck_modeling::{Matrix4, Vector3 as TruckVector3};
+use truck_polymesh::Rad;
+
+use crate::archetypes::Vector3;
+use crate::error::CADmiumError;
+use crate::pattern::PatternAxis;
+use crate::realization::Realization;
+
+// How a transform moves the solids. Every motion is rigid, so nothing gets stretched
+// or mirrored
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum Motion {
+    Translate(Vector3),
+    // in radians, following the right hand rule about the axis
+    Rotate {
+        axis: PatternAxis,
+        angle: f64,
+    },
+    // slides the solids so that one point step, like "Point-1", lands on another. Only
+    // moves them, without turning them to line anything else up
+    Align {
+        from_point_id: String,
+        to_point_id: String,
+    },
+}
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct Transform {
+    pub solid_ids: Vec<String>,
+    pub motion: Motion,
+    // leaves the originals where they are and moves new copies of them instead
+    pub copy: bool,
+}
+
+impl Transform {
+    pub fn new(solid_ids: Vec<String>, motion: Motion, copy: bool) -> Self {
+        Transform {
+            solid_ids,
+            motion,
+            copy,
+        }
+    }
+
+    pub fn matrix(&self, realized: &Realization) -> Result<Matrix4, CADmiumError> {
+        match &self.motion {
+            Motion::Translate(shift) => Ok(Matrix4::from_translation(TruckVector3::new(
+                shift.x, shift.y, shift.z,
+            ))),
+            Motion::Rotate { axis, angle } => {
+                let (origin, axis) = axis.resolve(realized)?;
+                let origin = TruckVector3::new(origin.x, origin.y, origin.z);
+                let axis = TruckVector3::new(axis.x, axis.y, axis.z);
+                Ok(Matrix4::from_translation(origin)
+                    * Matrix4::from_axis_angle(axis, Rad(*angle))
+                    * Matrix4::from_translation(-origin))
+            }
+            Motion::Align {
+                from_point_id,
+                to_point_id,
+            } => {
+                let point = |id: &String| {
+                    realized
+                        .points
+                        .get(id)
+                        .ok_or(CADmiumError::PointNotFound(id.clone()))
+                };
+                let (from, to) = (point(from_point_id)?, point(to_point_id)?);
+                Ok(Matrix4::from_translation(TruckVector3::new(
+                    to.x - from.x,
+                    to.y - from.y,
+                    to.z - from.z,
+                )))
+            }
+        }
+    }
+}
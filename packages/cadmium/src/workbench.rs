@@ -2,19 +2,36 @@ use serde::{Deserialize, Serialize};
 use tsify::Tsify;
 use wasm_bindgen::prelude::*;
 
-use crate::archetypes::{Plane, PlaneDescription, Point3, Vector3};
+use crate::archetypes::{Plane, Point3, Vector3};
+use crate::axis::{Axis, RealAxis};
+use crate::boolean::{Boolean, BooleanOperation};
+use crate::chamfer::Chamfer;
+use crate::draft::Draft;
 use crate::error::CADmiumError;
-use crate::extrusion::{fuse, Extrusion, ExtrusionMode};
+use crate::extrusion::{fuse, intersect, subtract, Extrusion, ExtrusionEnd, ExtrusionMode};
+use crate::fillet::Fillet;
+use crate::hole::Hole;
+use crate::loft::Loft;
+use crate::mirror::Mirror;
+use crate::offset_face::OffsetFace;
+use crate::pattern::{CircularPattern, LinearPattern, PatternSource};
+use crate::point::PointDescription;
 use crate::project::{RealPlane, RealSketch};
-use crate::realization::Realization;
+use crate::realization::{PlaneDescription, Realization};
+use crate::revolve::Revolve;
+use crate::rib::Rib;
+use crate::shell::Shell;
 use crate::sketch::Sketch;
 use crate::solid::Solid;
+use crate::split::Split;
 use crate::step::{Step, StepData};
+use crate::sweep::Sweep;
+use crate::transform::Transform;
 
 use std::collections::HashMap;
+use truck_modeling::Matrix4;
 
 // use truck_base::math::Vector3 as truck_vector3;
-use truck_shapeops::and as solid_and;
 
 #[derive(Tsify, Debug, Serialize, Deserialize)]
 #[tsify(into_wasm_abi, from_wasm_abi)]
@@ -34,6 +51,23 @@ impl Workbench {
                 ("Plane".to_owned(), 0),
                 ("Sketch".to_owned(), 0),
                 ("Extrusion".to_owned(), 0),
+                ("Revolve".to_owned(), 0),
+                ("Fillet".to_owned(), 0),
+                ("Chamfer".to_owned(), 0),
+                ("Hole".to_owned(), 0),
+                ("Draft".to_owned(), 0),
+                ("Shell".to_owned(), 0),
+                ("LinearPattern".to_owned(), 0),
+                ("CircularPattern".to_owned(), 0),
+                ("Mirror".to_owned(), 0),
+                ("Sweep".to_owned(), 0),
+                ("Loft".to_owned(), 0),
+                ("Rib".to_owned(), 0),
+                ("Split".to_owned(), 0),
+                ("Boolean".to_owned(), 0),
+                ("Transform".to_owned(), 0),
+                ("OffsetFace".to_owned(), 0),
+                ("Axis".to_owned(), 0),
             ]),
         };
 
@@ -136,6 +170,33 @@ impl Workbench {
         *counter += 1;
     }
 
+    // Adds a point that gets worked out from other geometry whenever the workbench is
+    // realized. It shares ids with the fixed points, like "Point-1"
+    pub fn add_derived_point(&mut self, name: &str, point: PointDescription) -> String {
+        let counter = self.step_counters.get_mut("Point").unwrap();
+        let new_step = Step::new_derived_point(name, point, *counter);
+        let new_step_id = new_step.unique_id.clone();
+        self.history.push(new_step);
+        *counter += 1;
+
+        new_step_id
+    }
+
+    pub fn add_axis(&mut self, name: &str, axis: Axis) -> String {
+        let counter = self.step_counters.entry("Axis".to_owned()).or_insert(0);
+        let axis_name = if name == "" {
+            format!("Axis {}", *counter + 1)
+        } else {
+            name.to_owned()
+        };
+        let new_step = Step::new_axis(&axis_name, axis, *counter);
+        let new_step_id = new_step.unique_id.clone();
+        self.history.push(new_step);
+        *counter += 1;
+
+        new_step_id
+    }
+
     pub fn add_plane(&mut self, name: &str, plane: Plane) -> String {
         let counter = self.step_counters.get_mut("Plane").unwrap();
         self.history.push(Step::new_plane(name, plane, *counter));
@@ -144,6 +205,19 @@ impl Workbench {
         self.plane_name_to_id(name).unwrap()
     }
 
+    // Adds a plane that gets worked out from other geometry whenever the workbench is
+    // realized. It shares ids with the fixed planes, like "Plane-3", so anything that
+    // takes a plane id can use it
+    pub fn add_derived_plane(&mut self, name: &str, plane: PlaneDescription) -> String {
+        let counter = self.step_counters.get_mut("Plane").unwrap();
+        let new_step = Step::new_derived_plane(name, plane, *counter);
+        let new_step_id = new_step.unique_id.clone();
+        self.history.push(new_step);
+        *counter += 1;
+
+        new_step_id
+    }
+
     pub fn plane_name_to_id(&self, plane_name: &str) -> Option<String> {
         for step in self.history.iter() {
             if step.name == plane_name {
@@ -152,7 +226,8 @@ impl Workbench {
                         plane: _,
                         width: _,
                         height: _,
-                    } => {
+                    }
+                    | StepData::DerivedPlane { .. } => {
                         return Some(step.unique_id.clone());
                     }
                     _ => {}
@@ -193,7 +268,8 @@ impl Workbench {
                             plane: _,
                             width: _,
                             height: _,
-                        } => {
+                        }
+                        | StepData::DerivedPlane { .. } => {
                             plane_exists = true;
                         }
                         _ => {}
@@ -224,6 +300,28 @@ impl Workbench {
         new_step_id
     }
 
+    // Adds a sketch on a plane that gets worked out from other geometry whenever the
+    // workbench is realized
+    pub fn add_sketch_to_derived_plane(
+        &mut self,
+        name: &str,
+        plane_description: PlaneDescription,
+    ) -> String {
+        let counter = self.step_counters.get_mut("Sketch").unwrap();
+        let sketch_name = if name == "" {
+            format!("Sketch {}", *counter + 1)
+        } else {
+            name.to_owned()
+        };
+
+        let new_step = Step::new_sketch_on_derived_plane(&sketch_name, plane_description, *counter);
+        let new_step_id = new_step.unique_id.clone();
+        self.history.push(new_step);
+        *counter += 1;
+
+        new_step_id
+    }
+
     pub fn add_extrusion(&mut self, name: &str, extrusion: Extrusion) -> u64 {
         // If the extrusion name is empty string, then we need to generate a new name
         // Let's use "Extrusion n" where n is the number of extrusions
@@ -239,6 +337,235 @@ impl Workbench {
         *counter - 1
     }
 
+    pub fn add_revolve(&mut self, name: &str, revolve: Revolve) -> u64 {
+        // Projects saved before revolves existed won't have a counter for them yet
+        let counter = self.step_counters.entry("Revolve".to_owned()).or_insert(0);
+        let revolve_name = if name == "" {
+            format!("Revolve {}", *counter + 1)
+        } else {
+            name.to_owned()
+        };
+        self.history
+            .push(Step::new_revolve(&revolve_name, revolve, *counter));
+        *counter += 1;
+        *counter - 1
+    }
+
+    pub fn add_fillet(&mut self, name: &str, fillet: Fillet) -> u64 {
+        let counter = self.step_counters.entry("Fillet".to_owned()).or_insert(0);
+        let fillet_name = if name == "" {
+            format!("Fillet {}", *counter + 1)
+        } else {
+            name.to_owned()
+        };
+        self.history
+            .push(Step::new_fillet(&fillet_name, fillet, *counter));
+        *counter += 1;
+        *counter - 1
+    }
+
+    pub fn add_chamfer(&mut self, name: &str, chamfer: Chamfer) -> u64 {
+        let counter = self.step_counters.entry("Chamfer".to_owned()).or_insert(0);
+        let chamfer_name = if name == "" {
+            format!("Chamfer {}", *counter + 1)
+        } else {
+            name.to_owned()
+        };
+        self.history
+            .push(Step::new_chamfer(&chamfer_name, chamfer, *counter));
+        *counter += 1;
+        *counter - 1
+    }
+
+    pub fn add_hole(&mut self, name: &str, hole: Hole) -> u64 {
+        let counter = self.step_counters.entry("Hole".to_owned()).or_insert(0);
+        let hole_name = if name == "" {
+            format!("Hole {}", *counter + 1)
+        } else {
+            name.to_owned()
+        };
+        self.history
+            .push(Step::new_hole(&hole_name, hole, *counter));
+        *counter += 1;
+        *counter - 1
+    }
+
+    pub fn add_draft(&mut self, name: &str, draft: Draft) -> u64 {
+        let counter = self.step_counters.entry("Draft".to_owned()).or_insert(0);
+        let draft_name = if name == "" {
+            format!("Draft {}", *counter + 1)
+        } else {
+            name.to_owned()
+        };
+        self.history
+            .push(Step::new_draft(&draft_name, draft, *counter));
+        *counter += 1;
+        *counter - 1
+    }
+
+    pub fn add_shell(&mut self, name: &str, shell: Shell) -> u64 {
+        let counter = self.step_counters.entry("Shell".to_owned()).or_insert(0);
+        let shell_name = if name == "" {
+            format!("Shell {}", *counter + 1)
+        } else {
+            name.to_owned()
+        };
+        self.history
+            .push(Step::new_shell(&shell_name, shell, *counter));
+        *counter += 1;
+        *counter - 1
+    }
+
+    pub fn add_linear_pattern(&mut self, name: &str, linear_pattern: LinearPattern) -> u64 {
+        let counter = self
+            .step_counters
+            .entry("LinearPattern".to_owned())
+            .or_insert(0);
+        let pattern_name = if name == "" {
+            format!("Linear Pattern {}", *counter + 1)
+        } else {
+            name.to_owned()
+        };
+        self.history.push(Step::new_linear_pattern(
+            &pattern_name,
+            linear_pattern,
+            *counter,
+        ));
+        *counter += 1;
+        *counter - 1
+    }
+
+    pub fn add_circular_pattern(&mut self, name: &str, circular_pattern: CircularPattern) -> u64 {
+        let counter = self
+            .step_counters
+            .entry("CircularPattern".to_owned())
+            .or_insert(0);
+        let pattern_name = if name == "" {
+            format!("Circular Pattern {}", *counter + 1)
+        } else {
+            name.to_owned()
+        };
+        self.history.push(Step::new_circular_pattern(
+            &pattern_name,
+            circular_pattern,
+            *counter,
+        ));
+        *counter += 1;
+        *counter - 1
+    }
+
+    pub fn add_mirror(&mut self, name: &str, mirror: Mirror) -> u64 {
+        let counter = self.step_counters.entry("Mirror".to_owned()).or_insert(0);
+        let mirror_name = if name == "" {
+            format!("Mirror {}", *counter + 1)
+        } else {
+            name.to_owned()
+        };
+        self.history
+            .push(Step::new_mirror(&mirror_name, mirror, *counter));
+        *counter += 1;
+        *counter - 1
+    }
+
+    pub fn add_sweep(&mut self, name: &str, sweep: Sweep) -> u64 {
+        let counter = self.step_counters.entry("Sweep".to_owned()).or_insert(0);
+        let sweep_name = if name == "" {
+            format!("Sweep {}", *counter + 1)
+        } else {
+            name.to_owned()
+        };
+        self.history
+            .push(Step::new_sweep(&sweep_name, sweep, *counter));
+        *counter += 1;
+        *counter - 1
+    }
+
+    pub fn add_loft(&mut self, name: &str, loft: Loft) -> u64 {
+        let counter = self.step_counters.entry("Loft".to_owned()).or_insert(0);
+        let loft_name = if name == "" {
+            format!("Loft {}", *counter + 1)
+        } else {
+            name.to_owned()
+        };
+        self.history
+            .push(Step::new_loft(&loft_name, loft, *counter));
+        *counter += 1;
+        *counter - 1
+    }
+
+    pub fn add_rib(&mut self, name: &str, rib: Rib) -> u64 {
+        let counter = self.step_counters.entry("Rib".to_owned()).or_insert(0);
+        let rib_name = if name == "" {
+            format!("Rib {}", *counter + 1)
+        } else {
+            name.to_owned()
+        };
+        self.history.push(Step::new_rib(&rib_name, rib, *counter));
+        *counter += 1;
+        *counter - 1
+    }
+
+    pub fn add_split(&mut self, name: &str, split: Split) -> u64 {
+        let counter = self.step_counters.entry("Split".to_owned()).or_insert(0);
+        let split_name = if name == "" {
+            format!("Split {}", *counter + 1)
+        } else {
+            name.to_owned()
+        };
+        self.history
+            .push(Step::new_split(&split_name, split, *counter));
+        *counter += 1;
+        *counter - 1
+    }
+
+    pub fn add_boolean(&mut self, name: &str, boolean: Boolean) -> u64 {
+        let counter = self.step_counters.entry("Boolean".to_owned()).or_insert(0);
+        let boolean_name = if name == "" {
+            format!("Boolean {}", *counter + 1)
+        } else {
+            name.to_owned()
+        };
+        self.history
+            .push(Step::new_boolean(&boolean_name, boolean, *counter));
+        *counter += 1;
+        *counter - 1
+    }
+
+    pub fn add_transform(&mut self, name: &str, transform: Transform) -> u64 {
+        let counter = self
+            .step_counters
+            .entry("Transform".to_owned())
+            .or_insert(0);
+        let transform_name = if name == "" {
+            format!("Transform {}", *counter + 1)
+        } else {
+            name.to_owned()
+        };
+        self.history
+            .push(Step::new_transform(&transform_name, transform, *counter));
+        *counter += 1;
+        *counter - 1
+    }
+
+    pub fn add_offset_face(&mut self, name: &str, offset_face: OffsetFace) -> u64 {
+        let counter = self
+            .step_counters
+            .entry("OffsetFace".to_owned())
+            .or_insert(0);
+        let offset_face_name = if name == "" {
+            format!("Offset Face {}", *counter + 1)
+        } else {
+            name.to_owned()
+        };
+        self.history.push(Step::new_offset_face(
+            &offset_face_name,
+            offset_face,
+            *counter,
+        ));
+        *counter += 1;
+        *counter - 1
+    }
+
     pub fn realize(&self, max_steps: u64) -> Realization {
         let mut realized = Realization::new();
         let max_steps = max_steps as usize; // just coerce the type once
@@ -257,6 +584,23 @@ impl Workbench {
                         .points
                         .insert(step.unique_id.to_owned(), point.clone());
                 }
+                StepData::DerivedPoint { point } => match point.resolve(&realized) {
+                    Ok(point) => {
+                        realized.points.insert(step.unique_id.to_owned(), point);
+                    }
+                    Err(e) => println!("Failed to find point {}: {}", step.name, e),
+                },
+                StepData::Axis { axis } => match axis.resolve(&realized) {
+                    Ok((origin, direction)) => {
+                        let ra = RealAxis {
+                            origin,
+                            direction,
+                            name: step.name.clone(),
+                        };
+                        realized.axes.insert(step.unique_id.to_owned(), ra);
+                    }
+                    Err(e) => println!("Failed to find axis {}: {}", step.name, e),
+                },
                 StepData::Plane {
                     plane,
                     width,
@@ -270,233 +614,800 @@ impl Workbench {
                     };
                     realized.planes.insert(step.unique_id.to_owned(), rp);
                 }
+                StepData::DerivedPlane {
+                    plane,
+                    width,
+                    height,
+                } => match plane.resolve(&realized) {
+                    Ok(plane) => {
+                        let rp = RealPlane {
+                            plane,
+                            width: *width,
+                            height: *height,
+                            name: step.name.clone(),
+                        };
+                        realized.planes.insert(step.unique_id.to_owned(), rp);
+                    }
+                    Err(e) => println!("Failed to find plane {}: {}", step.name, e),
+                },
                 StepData::Sketch {
                     width: _,
                     height: _,
                     plane_description,
                     sketch,
-                } => match plane_description {
-                    PlaneDescription::PlaneId(plane_id) => {
-                        if plane_id == "" {
-                            println!("Sketch {} has no plane", step.name);
-                            continue;
-                        }
-
-                        let plane = &realized.planes[plane_id];
-
-                        realized.sketches.insert(
-                            step.unique_id.to_owned(),
-                            (
-                                RealSketch::new(&plane.name, plane_id, plane, sketch),
-                                RealSketch::new(
-                                    &plane.name,
-                                    plane_id,
-                                    plane,
-                                    &sketch.split_intersections(false),
-                                ),
-                                step.name.clone(),
-                            ),
-                        );
-                    }
-                    PlaneDescription::SolidFace { solid_id, normal } => {
-                        let solid = &realized.solids[solid_id];
-                        let face = solid.get_face_by_normal(normal).unwrap();
-                        let oriented_surface = face.oriented_surface();
-
-                        println!("Surface: {:?}", oriented_surface);
-                        let sketch_plane;
-                        match oriented_surface {
-                            truck_modeling::geometry::Surface::Plane(p) => {
-                                let plane = Plane::from_truck(p);
-                                println!("Plane: {:?}", plane);
-                                sketch_plane = plane;
-                            }
-                            _ => {
-                                panic!("I only know how to put sketches on planes");
+                } => {
+                    let plane_id = match plane_description {
+                        PlaneDescription::PlaneId(plane_id) => {
+                            if plane_id == "" {
+                                println!("Sketch {} has no plane", step.name);
+                                continue;
                             }
+                            plane_id.clone()
                         }
+                        // every other kind of plane is worked out again each time, so
+                        // the sketch follows whatever it was derived from
+                        _ => {
+                            let sketch_plane = match plane_description.resolve(&realized) {
+                                Ok(plane) => plane,
+                                Err(e) => {
+                                    println!("Failed to find the plane of {}: {}", step.name, e);
+                                    continue;
+                                }
+                            };
+
+                            let new_plane_id = format!("derived_plane_for:{}", step.name);
+                            let rp = RealPlane {
+                                plane: sketch_plane,
+                                width: 90.0,
+                                height: 60.0,
+                                name: new_plane_id.clone(),
+                            };
+                            realized.planes.insert(new_plane_id.clone(), rp);
+                            new_plane_id
+                        }
+                    };
 
-                        let new_plane_id = format!("derived_plane_for:{}", step.name);
-
-                        let rp = RealPlane {
-                            plane: sketch_plane.clone(),
-                            width: 90.0,
-                            height: 60.0,
-                            name: new_plane_id.clone(),
-                        };
-                        realized.planes.insert(new_plane_id.clone(), rp);
-                        let rp = &realized.planes[&new_plane_id];
-
-                        realized.sketches.insert(
-                            step.unique_id.to_owned(),
-                            (
-                                RealSketch::new(&new_plane_id, &new_plane_id, &rp, sketch),
-                                RealSketch::new(
-                                    &new_plane_id,
-                                    &new_plane_id,
-                                    &rp,
-                                    &sketch.split_intersections(false),
-                                ),
-                                step.name.clone(),
+                    let plane = &realized.planes[&plane_id];
+                    realized.sketches.insert(
+                        step.unique_id.to_owned(),
+                        (
+                            RealSketch::new(&plane.name, &plane_id, plane, sketch),
+                            RealSketch::new(
+                                &plane.name,
+                                &plane_id,
+                                plane,
+                                &sketch.split_intersections(false),
                             ),
-                        );
-                    }
-                },
+                            step.name.clone(),
+                        ),
+                    );
+                }
                 StepData::Extrusion { extrusion } => {
-                    let (_sketch, split_sketch, _name) = &realized.sketches[&extrusion.sketch_id];
+                    let (sketch, split_sketch, _name) = &realized.sketches[&extrusion.sketch_id];
                     let plane = &realized.planes[&split_sketch.plane_id];
 
-                    match &extrusion.mode {
-                        ExtrusionMode::New => {
-                            // if this extrusion is in mode "New" then this old behavior is correct!
+                    // thin walls are picked by segments of the sketch as it was drawn, which
+                    // get new ids in the split sketch that the extrusion is built from
+                    let mut resolved = extrusion.clone();
+                    if let Some(thin) = resolved.thin.as_mut() {
+                        thin.segments = thin
+                            .segments
+                            .iter()
+                            .filter_map(|segment| {
+                                let piece = sketch.split_piece(split_sketch, segment);
+                                if piece.is_none() {
+                                    println!(
+                                        "Extrusion {} has no segment {:?} to thicken",
+                                        step.name, segment
+                                    );
+                                }
+                                piece
+                            })
+                            .collect();
+                    }
 
-                            let solids = Solid::from_extrusion(
-                                step.name.clone(),
-                                plane,
-                                split_sketch,
-                                extrusion,
-                            );
+                    // pin down where the extrusion stops before building it
+                    let (start, end) = match resolved.extent(plane, split_sketch, &realized) {
+                        Ok(extent) => extent,
+                        Err(e) => {
+                            println!("Failed to find the end of {}: {}", step.name, e);
+                            continue;
+                        }
+                    };
+                    if end - start < 1e-9 {
+                        println!("Extrusion {} doesn't go anywhere", step.name);
+                        continue;
+                    }
+                    resolved.offset = start;
+                    resolved.length = end;
+                    resolved.end = ExtrusionEnd::Blind;
+                    resolved.second_length = 0.0;
+
+                    let new_solids =
+                        Solid::from_extrusion(step.name.clone(), plane, split_sketch, &resolved);
+
+                    realized.tools.insert(
+                        step.unique_id.clone(),
+                        (new_solids.clone(), extrusion.mode.clone()),
+                    );
+                    merge_solids(&mut realized, &extrusion.mode, new_solids);
+                }
+                StepData::Revolve { revolve } => {
+                    let (sketch, split_sketch, _name) = &realized.sketches[&revolve.sketch_id];
+                    let plane = &realized.planes[&split_sketch.plane_id];
 
-                            for (name, solid) in solids {
-                                realized.solids.insert(name, solid);
+                    // the axis is looked up on the unsplit sketch so that a line which
+                    // crosses the profile still counts as a single segment
+                    let (axis_origin, axis_direction) =
+                        match revolve.axis.resolve(&realized, plane, sketch) {
+                            Some(axis) => axis,
+                            None => {
+                                println!("Revolve {} has no valid axis", step.name);
+                                continue;
                             }
-                        }
-                        ExtrusionMode::Add(merge_scope) => {
-                            // if this extrusion is in mode "Add" Then we need to merge the resulting solids
-                            // with each of the solids listed in the merge scope
+                        };
 
-                            let new_solids = Solid::from_extrusion(
-                                step.name.clone(),
-                                plane,
-                                split_sketch,
-                                extrusion,
-                            );
+                    let new_solids = Solid::from_revolve(
+                        step.name.clone(),
+                        plane,
+                        split_sketch,
+                        revolve,
+                        &axis_origin,
+                        &axis_direction,
+                    );
+
+                    realized.tools.insert(
+                        step.unique_id.clone(),
+                        (new_solids.clone(), revolve.mode.clone()),
+                    );
+                    merge_solids(&mut realized, &revolve.mode, new_solids);
+                }
+                StepData::Sweep { sweep } => {
+                    let (_sketch, profile_sketch, _name) =
+                        &realized.sketches[&sweep.profile_sketch_id];
+                    let plane = &realized.planes[&profile_sketch.plane_id];
+
+                    // like a revolve axis, the path comes from the unsplit sketch so that
+                    // its lines and arcs keep the ids they were drawn with
+                    let (path_sketch, _split_sketch, _name) =
+                        &realized.sketches[&sweep.path_sketch_id];
+                    let path_plane = &realized.planes[&path_sketch.plane_id];
+                    let path = match sweep.path_pieces(path_plane, path_sketch) {
+                        Ok(path) => path,
+                        Err(e) => {
+                            println!("Failed to follow the path of {}: {}", step.name, e);
+                            continue;
+                        }
+                    };
 
-                            // NO LONGER NEEDED
-                            // // this is some bullshit, but bear with me. To make the solids merge properly we need to
-                            // // lengthen the extrusion a tiny bit, basically build in some buffer
-                            // let mut longer_extrusion = extrusion.clone();
-                            // longer_extrusion.length += 0.001;
-                            // longer_extrusion.offset -= 0.001;
-                            // let solids = Solid::from_extrusion(
-                            //     step.name.clone(),
-                            //     plane,
-                            //     split_sketch,
-                            //     &longer_extrusion,
-                            // );
-
-                            for existing_solid_name in merge_scope {
-                                let mut existing_solid_to_merge_with =
-                                    realized.solids.remove(existing_solid_name).unwrap();
-
-                                // merge this existing solid with as many of the new solids as possible
-                                for (_, new_solid) in new_solids.iter() {
-                                    // let new_candidate = translated(
-                                    //     &solid.truck_solid,
-                                    //     TruckVector3::new(0.0, 0.0, 1.0),
-                                    // );
-                                    // println!("\nTranslated new candidate: {:?}", new_candidate);
-
-                                    // let result =
-                                    //     solid_or(&existing_solid.truck_solid, &new_candidate, 0.1);
-
-                                    let fused = fuse(
-                                        &existing_solid_to_merge_with.truck_solid,
-                                        &new_solid.truck_solid,
-                                    );
+                    let new_solids = match Solid::from_sweep(
+                        step.name.clone(),
+                        plane,
+                        profile_sketch,
+                        sweep,
+                        &path,
+                    ) {
+                        Ok(new_solids) => new_solids,
+                        Err(e) => {
+                            println!("Failed to sweep {}: {}", step.name, e);
+                            continue;
+                        }
+                    };
 
-                                    match fused {
-                                        Some(s) => {
-                                            existing_solid_to_merge_with = Solid::from_truck_solid(
-                                                existing_solid_name.to_owned(),
-                                                s,
-                                            );
-                                        }
-                                        None => {
-                                            println!("Failed to merge with OR");
-                                        }
-                                    }
-                                }
+                    realized.tools.insert(
+                        step.unique_id.clone(),
+                        (new_solids.clone(), sweep.mode.clone()),
+                    );
+                    merge_solids(&mut realized, &sweep.mode, new_solids);
+                }
+                StepData::Loft { loft } => {
+                    let profiles: Option<Vec<(&RealPlane, &RealSketch)>> = loft
+                        .profiles
+                        .iter()
+                        .map(|profile| {
+                            let (_sketch, split_sketch, _name) =
+                                realized.sketches.get(&profile.sketch_id)?;
+                            Some((&realized.planes[&split_sketch.plane_id], split_sketch))
+                        })
+                        .collect();
+                    let profiles = match profiles {
+                        Some(profiles) => profiles,
+                        None => {
+                            println!("Loft {} is missing one of its sketches", step.name);
+                            continue;
+                        }
+                    };
 
-                                realized.solids.insert(
-                                    existing_solid_name.to_owned(),
-                                    existing_solid_to_merge_with,
-                                );
-                            }
+                    let new_solids = match Solid::from_loft(step.name.clone(), &profiles, loft) {
+                        Ok(new_solids) => new_solids,
+                        Err(e) => {
+                            println!("Failed to loft {}: {}", step.name, e);
+                            continue;
                         }
+                    };
 
-                        ExtrusionMode::Remove(merge_scope) => {
-                            // If this extrusion is in mode "Remove" then we need to subtract the resulting solid
-                            // with each of the solids listed in the merge scope
-                            println!("Okay, let's remove");
-                            let new_solids = Solid::from_extrusion(
-                                step.name.clone(),
-                                plane,
-                                split_sketch,
-                                extrusion,
-                            );
+                    realized.tools.insert(
+                        step.unique_id.clone(),
+                        (new_solids.clone(), loft.mode.clone()),
+                    );
+                    merge_solids(&mut realized, &loft.mode, new_solids);
+                }
+                StepData::Fillet { fillet } => {
+                    let solid = match realized.solids.get(&fillet.solid_id) {
+                        Some(solid) => solid,
+                        None => {
+                            println!("Fillet {} has no solid to work on", step.name);
+                            continue;
+                        }
+                    };
 
-                            for existing_solid_name in merge_scope {
-                                let mut existing_solid_to_merge_with =
-                                    realized.solids.remove(existing_solid_name).unwrap();
-
-                                // merge this existing solid with as many of the new solids as possible
-                                for (_, new_solid) in new_solids.iter() {
-                                    // let translated_solid = translated(
-                                    //     &solid.truck_solid,
-                                    //     TruckVector3::new(0.0, 0.0, 1.0),
-                                    // );
-                                    // println!("\nTranslated new candidate: {:?}", new_candidate);
-
-                                    // let result =
-                                    //     solid_or(&existing_solid.truck_solid, &new_candidate, 0.1);
-
-                                    let punch = new_solid.truck_solid.clone();
-                                    // punch.not();
-                                    println!("Have a punch");
-
-                                    let cleared = solid_and(
-                                        &existing_solid_to_merge_with.truck_solid,
-                                        &punch,
-                                        0.1,
-                                    );
+                    match Solid::from_fillet(solid, fillet) {
+                        Ok(filleted) => {
+                            realized.solids.insert(fillet.solid_id.clone(), filleted);
+                        }
+                        Err(e) => println!("Failed to fillet {}: {}", step.name, e),
+                    }
+                }
+                StepData::Chamfer { chamfer } => {
+                    let solid = match realized.solids.get(&chamfer.solid_id) {
+                        Some(solid) => solid,
+                        None => {
+                            println!("Chamfer {} has no solid to work on", step.name);
+                            continue;
+                        }
+                    };
 
-                                    println!("have cleared");
-
-                                    match cleared {
-                                        Some(s) => {
-                                            println!("Merged with AND");
-                                            // println!("{:?}", s);
-                                            existing_solid_to_merge_with = Solid::from_truck_solid(
-                                                existing_solid_name.to_owned(),
-                                                s,
-                                            );
-                                        }
-                                        None => {
-                                            println!("Failed to merge with AND");
-                                        }
-                                    }
-                                }
+                    match Solid::from_chamfer(solid, chamfer) {
+                        Ok(chamfered) => {
+                            realized.solids.insert(chamfer.solid_id.clone(), chamfered);
+                        }
+                        Err(e) => println!("Failed to chamfer {}: {}", step.name, e),
+                    }
+                }
+                StepData::Hole { hole } => {
+                    let (sketch, _split_sketch, _name) = &realized.sketches[&hole.sketch_id];
+                    let plane = &realized.planes[&sketch.plane_id];
+                    let callouts = hole.callouts(plane, sketch);
+
+                    for solid_id in hole.solids.iter() {
+                        let solid = match realized.solids.get(solid_id) {
+                            Some(solid) => solid,
+                            None => {
+                                println!("Hole {} can't find solid {}", step.name, solid_id);
+                                continue;
+                            }
+                        };
 
-                                realized.solids.insert(
-                                    existing_solid_name.to_owned(),
-                                    existing_solid_to_merge_with,
-                                );
-                                println!("inserted the solid back in")
+                        match Solid::from_holes(solid, hole, &callouts) {
+                            Ok(drilled) => {
+                                realized.solids.insert(solid_id.clone(), drilled);
                             }
+                            Err(e) => println!("Failed to drill {}: {}", step.name, e),
                         }
                     }
                 }
-            }
-        }
-
-        realized
+                StepData::Draft { draft } => {
+                    let solid = match realized.solids.get(&draft.solid_id) {
+                        Some(solid) => solid,
+                        None => {
+                            println!("Draft {} has no solid to work on", step.name);
+                            continue;
+                        }
+                    };
+                    let neutral = match realized.planes.get(&draft.neutral_plane_id) {
+                        Some(plane) => plane,
+                        None => {
+                            println!("Draft {} has no neutral plane", step.name);
+                            continue;
+                        }
+                    };
+
+                    match Solid::from_draft(solid, draft, neutral) {
+                        Ok(drafted) => {
+                            realized.solids.insert(draft.solid_id.clone(), drafted);
+                        }
+                        Err(e) => println!("Failed to draft {}: {}", step.name, e),
+                    }
+                }
+                StepData::Shell { shell } => {
+                    let solid = match realized.solids.get(&shell.solid_id) {
+                        Some(solid) => solid,
+                        None => {
+                            println!("Shell {} has no solid to work on", step.name);
+                            continue;
+                        }
+                    };
+
+                    match Solid::from_shell(solid, shell) {
+                        Ok(hollowed) => {
+                            realized.solids.insert(shell.solid_id.clone(), hollowed);
+                        }
+                        Err(e) => println!("Failed to shell {}: {}", step.name, e),
+                    }
+                }
+                StepData::OffsetFace { offset_face } => {
+                    let solid = match realized.solids.get(&offset_face.solid_id) {
+                        Some(solid) => solid,
+                        None => {
+                            println!("Offset face {} has no solid to work on", step.name);
+                            continue;
+                        }
+                    };
+
+                    match Solid::from_offset_face(solid, offset_face) {
+                        Ok(moved) => {
+                            realized.solids.insert(offset_face.solid_id.clone(), moved);
+                        }
+                        Err(e) => println!("Failed to offset faces for {}: {}", step.name, e),
+                    }
+                }
+                StepData::Rib { rib } => {
+                    let (sketch, split_sketch, _name) = &realized.sketches[&rib.sketch_id];
+                    let plane = &realized.planes[&split_sketch.plane_id];
+
+                    // like a thin wall, the rib's chain is picked by a segment of the sketch
+                    // as it was drawn
+                    let mut resolved = rib.clone();
+                    resolved.segment = match sketch.split_piece(split_sketch, &rib.segment) {
+                        Some(piece) => piece,
+                        None => {
+                            println!(
+                                "Rib {} has no segment {:?} to grow from",
+                                step.name, rib.segment
+                            );
+                            continue;
+                        }
+                    };
+                    let solid = match realized.solids.get(&rib.solid_id) {
+                        Some(solid) => solid,
+                        None => {
+                            println!("Rib {} has no solid to work on", step.name);
+                            continue;
+                        }
+                    };
+
+                    match Solid::from_rib(solid, &resolved, plane, split_sketch) {
+                        Ok(ribbed) => {
+                            realized.solids.insert(rib.solid_id.clone(), ribbed);
+                        }
+                        Err(e) => println!("Failed to add rib {}: {}", step.name, e),
+                    }
+                }
+                StepData::Split { split } => {
+                    let solid = match realized.solids.get(&split.solid_id) {
+                        Some(solid) => solid,
+                        None => {
+                            println!("Split {} has no solid to work on", step.name);
+                            continue;
+                        }
+                    };
+
+                    match Solid::from_split(step.name.clone(), solid, split, &realized) {
+                        Ok(pieces) => {
+                            // either side of the cut may itself be in several bodies
+                            realized.solids.remove(&split.solid_id);
+                            for (piece_name, piece) in pieces {
+                                insert_bodies(&mut realized, &piece_name, piece.split_bodies());
+                            }
+                        }
+                        Err(e) => println!("Failed to split {}: {}", step.name, e),
+                    }
+                }
+                StepData::LinearPattern { linear_pattern } => {
+                    place_copies(
+                        &mut realized,
+                        &step.name,
+                        &linear_pattern.source,
+                        &linear_pattern.transforms(),
+                        false,
+                    );
+                }
+                StepData::CircularPattern { circular_pattern } => {
+                    let transforms = match circular_pattern.transforms(&realized) {
+                        Ok(transforms) => transforms,
+                        Err(e) => {
+                            println!("Failed to find the axis of {}: {}", step.name, e);
+                            continue;
+                        }
+                    };
+                    place_copies(
+                        &mut realized,
+                        &step.name,
+                        &circular_pattern.source,
+                        &transforms,
+                        false,
+                    );
+                }
+                StepData::Mirror { mirror } => {
+                    let transform = match mirror.transform(&realized) {
+                        Ok(transform) => transform,
+                        Err(e) => {
+                            println!("Failed to find the mirror plane of {}: {}", step.name, e);
+                            continue;
+                        }
+                    };
+                    for source in mirror.sources.iter() {
+                        place_copies(
+                            &mut realized,
+                            &step.name,
+                            source,
+                            &[transform],
+                            mirror.merge,
+                        );
+                    }
+                }
+                StepData::Boolean { boolean } => {
+                    if !realized.solids.contains_key(&boolean.target_id) {
+                        println!("Boolean {} has no solid to work on", step.name);
+                        continue;
+                    }
+                    let unusable = boolean.tool_ids.iter().find(|tool_id| {
+                        **tool_id == boolean.target_id || !realized.solids.contains_key(*tool_id)
+                    });
+                    if let Some(tool_id) = unusable {
+                        println!("Boolean {} can't use {} as a tool", step.name, tool_id);
+                        continue;
+                    }
+
+                    let tools: Vec<Solid> = boolean
+                        .tool_ids
+                        .iter()
+                        .map(|tool_id| {
+                            if boolean.keep_tools {
+                                realized.solids[tool_id].clone()
+                            } else {
+                                realized.solids.remove(tool_id).unwrap()
+                            }
+                        })
+                        .collect();
+                    apply_boolean(&mut realized, boolean, tools);
+                }
+                StepData::Transform { transform } => {
+                    let matrix = match transform.matrix(&realized) {
+                        Ok(matrix) => matrix,
+                        Err(e) => {
+                            println!("Failed to work out the motion of {}: {}", step.name, e);
+                            continue;
+                        }
+                    };
+                    let mut copy_index = 0;
+                    for solid_id in transform.solid_ids.iter() {
+                        let mut moved = match realized.solids.get(solid_id) {
+                            Some(solid) => solid.transformed(matrix),
+                            None => {
+                                println!(
+                                    "Transform {} has no solid {} to move",
+                                    step.name, solid_id
+                                );
+                                continue;
+                            }
+                        };
+                        if !transform.copy {
+                            realized.solids.insert(solid_id.clone(), moved);
+                            continue;
+                        }
+
+                        // copies are new solids named after the step, and bring their
+                        // hole callouts along
+                        moved.name = loop {
+                            let name = format!("{}:{}", step.name, copy_index);
+                            copy_index += 1;
+                            if !realized.solids.contains_key(&name) {
+                                break name;
+                            }
+                        };
+                        realized.solids.insert(moved.name.clone(), moved);
+                    }
+                }
+            }
+        }
+
+        realized
+    }
+}
+
+// Puts freshly built solids into the realization. In mode "New" they are simply added,
+// otherwise they are merged into, removed from or intersected with each of the solids
+// listed in the merge scope
+fn merge_solids(
+    realized: &mut Realization,
+    mode: &ExtrusionMode,
+    new_solids: HashMap<String, Solid>,
+) {
+    match mode {
+        ExtrusionMode::New => {
+            for (name, solid) in new_solids {
+                realized.solids.insert(name, solid);
+            }
+        }
+        ExtrusionMode::Add(merge_scope) => {
+            for existing_solid_name in merge_scope {
+                if !realized.solids.contains_key(existing_solid_name) {
+                    println!(
+                        "Can't add anything to missing solid {}",
+                        existing_solid_name
+                    );
+                }
+            }
+
+            let mut new_names: Vec<&String> = new_solids.keys().collect();
+            new_names.sort();
+            for new_name in new_names {
+                // a new solid that reaches across several solids in scope joins them all
+                // into the first one it touches. One that touches none stays on its own
+                let mut merged_into: Option<String> = None;
+
+                for existing_solid_name in merge_scope {
+                    if merged_into.as_ref() == Some(existing_solid_name) {
+                        continue;
+                    }
+                    // solids in scope that were joined into another one are gone by now
+                    let existing_solid = match realized.solids.get(existing_solid_name) {
+                        Some(solid) => solid,
+                        None => continue,
+                    };
+                    let so_far = match &merged_into {
+                        Some(name) => &realized.solids[name].truck_solid,
+                        None => &new_solids[new_name].truck_solid,
+                    };
+
+                    let mut bodies = match fuse(&existing_solid.truck_solid, so_far) {
+                        Some(bodies) if bodies.len() == 1 => bodies,
+                        Some(_) => continue,
+                        None => {
+                            println!("Failed to merge {} with OR", new_name);
+                            continue;
+                        }
+                    };
+
+                    let merged = match &merged_into {
+                        None => existing_solid.with_truck_solid(bodies.remove(0)),
+                        Some(name) => {
+                            let mut merged =
+                                realized.solids[name].with_truck_solid(bodies.remove(0));
+                            merged.holes.extend(existing_solid.holes.iter().cloned());
+                            realized.solids.remove(existing_solid_name);
+                            merged
+                        }
+                    };
+                    let name = merged_into.get_or_insert(existing_solid_name.clone());
+                    realized.solids.insert(name.clone(), merged);
+                }
+
+                if merged_into.is_none() {
+                    realized
+                        .solids
+                        .insert(new_name.clone(), new_solids[new_name].clone());
+                }
+            }
+        }
+        ExtrusionMode::Remove(merge_scope) => {
+            for existing_solid_name in merge_scope {
+                let existing_solid = match realized.solids.remove(existing_solid_name) {
+                    Some(solid) => solid,
+                    None => {
+                        println!(
+                            "Can't remove anything from missing solid {}",
+                            existing_solid_name
+                        );
+                        continue;
+                    }
+                };
+
+                // cut every new solid out of every body that's left, which may split
+                // the existing solid into several bodies along the way
+                let mut bodies = vec![existing_solid];
+                for (_, new_solid) in new_solids.iter() {
+                    bodies = bodies
+                        .into_iter()
+                        .flat_map(|body| {
+                            match subtract(&body.truck_solid, &new_solid.truck_solid) {
+                                Some(s) => body.with_truck_solid(s).split_bodies(),
+                                None => {
+                                    println!(
+                                        "Failed to cut {} out of {}",
+                                        new_solid.name, body.name
+                                    );
+                                    vec![body]
+                                }
+                            }
+                        })
+                        .collect();
+                }
+
+                insert_bodies(realized, existing_solid_name, bodies);
+            }
+        }
+        ExtrusionMode::Intersect(merge_scope) => {
+            for existing_solid_name in merge_scope {
+                let existing_solid = match realized.solids.remove(existing_solid_name) {
+                    Some(solid) => solid,
+                    None => {
+                        println!("Can't intersect with missing solid {}", existing_solid_name);
+                        continue;
+                    }
+                };
+
+                // the new solids never overlap each other, so what the existing solid
+                // shares with each of them can simply be collected
+                let mut bodies = vec![];
+                for (_, new_solid) in new_solids.iter() {
+                    match intersect(&existing_solid.truck_solid, &new_solid.truck_solid) {
+                        Some(s) => bodies.extend(existing_solid.with_truck_solid(s).split_bodies()),
+                        None => {
+                            println!(
+                                "Failed to intersect {} with {}",
+                                existing_solid.name, new_solid.name
+                            );
+                        }
+                    }
+                }
+
+                insert_bodies(realized, existing_solid_name, bodies);
+            }
+        }
+    }
+}
+
+// Combines a boolean's tools with its target, one tool after another. Whatever is left of
+// the target keeps its name, and any extra bodies are numbered after it
+fn apply_boolean(realized: &mut Realization, boolean: &Boolean, tools: Vec<Solid>) {
+    let target = match realized.solids.remove(&boolean.target_id) {
+        Some(solid) => solid,
+        None => return,
+    };
+
+    let mut bodies = vec![target];
+    for tool in tools {
+        bodies = match boolean.operation {
+            BooleanOperation::Union => {
+                // the tool joins every body it touches into the first of them, and
+                // stays a body of its own if it touches none
+                let mut result: Vec<Solid> = vec![];
+                let mut joined_at: Option<usize> = None;
+                for body in bodies {
+                    let so_far = match joined_at {
+                        Some(index) => &result[index].truck_solid,
+                        None => &tool.truck_solid,
+                    };
+                    match fuse(&body.truck_solid, so_far) {
+                        Some(mut fused) if fused.len() == 1 => match joined_at {
+                            None => {
+                                let mut merged = body.with_truck_solid(fused.remove(0));
+                                merged.holes.extend(tool.holes.iter().cloned());
+                                joined_at = Some(result.len());
+                                result.push(merged);
+                            }
+                            Some(index) => {
+                                let mut merged = result[index].with_truck_solid(fused.remove(0));
+                                merged.holes.extend(body.holes.iter().cloned());
+                                result[index] = merged;
+                            }
+                        },
+                        Some(_) => result.push(body),
+                        None => {
+                            println!("Failed to join {} to {}", tool.name, body.name);
+                            result.push(body);
+                        }
+                    }
+                }
+                if joined_at.is_none() {
+                    result.push(tool);
+                }
+                result
+            }
+            BooleanOperation::Subtract | BooleanOperation::Intersect => bodies
+                .into_iter()
+                .flat_map(|body| {
+                    let result = match boolean.operation {
+                        BooleanOperation::Subtract => {
+                            subtract(&body.truck_solid, &tool.truck_solid)
+                        }
+                        _ => intersect(&body.truck_solid, &tool.truck_solid),
+                    };
+                    match result {
+                        Some(s) => body.with_truck_solid(s).split_bodies(),
+                        None => {
+                            println!("Failed to combine {} with {}", tool.name, body.name);
+                            vec![body]
+                        }
+                    }
+                })
+                .collect(),
+        };
+    }
+
+    insert_bodies(realized, &boolean.target_id, bodies);
+}
+
+// Puts a moved copy of a pattern's source in place for each transform. Copies of a
+// step's bodies get merged the same way the step's own bodies were, while copies of a
+// whole solid become new solids named after the pattern, or are fused with the solid
+// they came from
+fn place_copies(
+    realized: &mut Realization,
+    step_name: &str,
+    source: &PatternSource,
+    transforms: &[Matrix4],
+    fuse_with_source: bool,
+) {
+    let (mut originals, mode) = match source {
+        PatternSource::Step(step_id) => match realized.tools.get(step_id) {
+            Some((bodies, mode)) => (bodies.values().cloned().collect::<Vec<_>>(), mode.clone()),
+            None => {
+                println!("{} has no step {} to repeat", step_name, step_id);
+                return;
+            }
+        },
+        PatternSource::Solid(solid_id) => match realized.solids.get(solid_id) {
+            Some(solid) if fuse_with_source => (
+                vec![solid.clone()],
+                ExtrusionMode::Add(vec![solid_id.clone()]),
+            ),
+            Some(solid) => (vec![solid.clone()], ExtrusionMode::New),
+            None => {
+                println!("{} has no solid {} to repeat", step_name, solid_id);
+                return;
+            }
+        },
+    };
+    originals.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut copies = HashMap::new();
+    let mut index = 0;
+    for transform in transforms {
+        for original in originals.iter() {
+            let name = loop {
+                let name = format!("{}:{}", step_name, index);
+                index += 1;
+                if !realized.solids.contains_key(&name) {
+                    break name;
+                }
+            };
+            let mut copy = original.transformed(*transform);
+            copy.name = name.clone();
+            copies.insert(name, copy);
+        }
+    }
+
+    merge_solids(realized, &mode, copies);
+}
+
+// Puts the bodies that are left of a solid back into the realization. The first one keeps
+// the solid's name and the rest are numbered after it
+fn insert_bodies(realized: &mut Realization, solid_name: &str, bodies: Vec<Solid>) {
+    let mut suffix = 0;
+    for (index, mut body) in bodies.into_iter().enumerate() {
+        body.name = solid_name.to_owned();
+        if index > 0 {
+            loop {
+                suffix += 1;
+                body.name = format!("{}:{}", solid_name, suffix);
+                if !realized.solids.contains_key(&body.name) {
+                    break;
+                }
+            }
+        }
+        realized.solids.insert(body.name.clone(), body);
     }
 }
 
 #[cfg(test)]
 pub mod tests {
-    use crate::extrusion::Direction;
+    use std::f64::consts::PI;
+
+    use crate::archetypes::EdgeDescription;
+    use crate::chamfer::ChamferMode;
+    use crate::extrusion::{Direction, ThinWall, WallSide};
+    use crate::hole::{HoleDepth, HoleProfile};
+    use crate::loft::LoftProfile;
+    use crate::mirror::MirrorPlane;
+    use crate::pattern::{PatternAxis, PatternDirection};
+    use crate::project::tests::create_test_project;
+    use crate::revolve::RevolveAxis;
+    use crate::split::{SplitKeep, SplitTool};
+    use crate::sweep::{PathSegment, SweepOrientation};
+    use crate::transform::Motion;
 
     use super::*;
 
@@ -546,4 +1457,2186 @@ pub mod tests {
         assert_eq!(realization.sketches.len(), 1);
         assert_eq!(realization.solids.len(), 1);
     }
+    #[test]
+    fn make_workbench_with_revolve() {
+        let mut wb = Workbench::new("Test Workbench");
+        wb.add_sketch_to_plane("Sketch 1", "Plane-0");
+        let s = wb.get_sketch_mut("Sketch 1").unwrap();
+
+        // a washer-like cross section, offset from the secondary axis of the Front plane
+        let ll = s.add_point(10.0, 0.0);
+        let lr = s.add_point(20.0, 0.0);
+        let ul = s.add_point(10.0, 5.0);
+        let ur = s.add_point(20.0, 5.0);
+        s.add_segment(ll, lr);
+        s.add_segment(lr, ur);
+        s.add_segment(ur, ul);
+        s.add_segment(ul, ll);
+
+        let revolve = Revolve::new(
+            "Sketch-0".to_owned(),
+            vec![0],
+            RevolveAxis::PlaneSecondary,
+            2.0 * PI,
+            ExtrusionMode::New,
+        );
+        wb.add_revolve("Rev1", revolve);
+
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 1);
+        assert!(realization.solids.contains_key("Rev1:0"));
+    }
+    #[test]
+    fn make_workbench_with_fillet() {
+        let mut p = create_test_project();
+        let wb = p.workbenches.get_mut(0).unwrap();
+
+        // the top edge of the 40x40x25 block that lies in the Front plane
+        let edge = EdgeDescription {
+            start: Point3::new(0.0, 0.0, 40.0),
+            end: Point3::new(40.0, 0.0, 40.0),
+        };
+        wb.add_fillet("Fillet1", Fillet::new("Ext1:0".to_owned(), vec![edge], 5.0));
+
+        let before = wb.realize(wb.history.len() as u64 - 1);
+        let after = wb.realize(1000);
+        assert_eq!(after.solids.len(), 1);
+        assert_ne!(before.solids["Ext1:0"].crc32, after.solids["Ext1:0"].crc32);
+    }
+    #[test]
+    fn make_workbench_with_chamfer() {
+        let mut p = create_test_project();
+        let wb = p.workbenches.get_mut(0).unwrap();
+
+        let edge = EdgeDescription {
+            start: Point3::new(0.0, 0.0, 40.0),
+            end: Point3::new(40.0, 0.0, 40.0),
+        };
+        let chamfer = Chamfer::new(
+            "Ext1:0".to_owned(),
+            vec![edge],
+            ChamferMode::TwoDistances {
+                distance_0: 3.0,
+                distance_1: 6.0,
+                first_face_normal: Vector3::new(0.0, 0.0, 1.0),
+            },
+        );
+        wb.add_chamfer("Chamfer1", chamfer);
+
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 1);
+
+        // the block runs back from the Front plane to y = -25, so the chamfer reaches 3
+        // back across the top face and 6 down the front one
+        let solid = &realization.solids["Ext1:0"];
+        let corners: Vec<_> = solid.truck_solid.vertex_iter().map(|v| v.point()).collect();
+        let has_corner = |x: f64, y: f64, z: f64| {
+            corners
+                .iter()
+                .any(|p| (p.x - x).abs() < 1e-6 && (p.y - y).abs() < 1e-6 && (p.z - z).abs() < 1e-6)
+        };
+        for x in [0.0, 40.0] {
+            assert!(has_corner(x, -3.0, 40.0));
+            assert!(has_corner(x, 0.0, 34.0));
+            assert!(!has_corner(x, 0.0, 40.0));
+        }
+    }
+    // A 40x40 square sketched on the Top plane, as "Sketch-0"
+    fn square_workbench() -> Workbench {
+        let mut wb = Workbench::new("Test Workbench");
+        wb.add_sketch_to_plane("Sketch 1", "Plane-2");
+        let s = wb.get_sketch_mut("Sketch 1").unwrap();
+        let ll = s.add_point(0.0, 0.0);
+        let lr = s.add_point(40.0, 0.0);
+        let ul = s.add_point(0.0, 40.0);
+        let ur = s.add_point(40.0, 40.0);
+        s.add_segment(ll, lr);
+        s.add_segment(lr, ur);
+        s.add_segment(ur, ul);
+        s.add_segment(ul, ll);
+
+        wb
+    }
+
+    // A 40x40x25 block sitting on the Top plane, and a plane 5 units above its top face
+    fn block_workbench() -> Workbench {
+        let mut wb = square_workbench();
+
+        let extrusion = Extrusion::new(
+            "Sketch-0".to_owned(),
+            vec![0],
+            25.0,
+            0.0,
+            Direction::Normal,
+            ExtrusionMode::New,
+        );
+        wb.add_extrusion("Ext1", extrusion);
+
+        let above = Plane::new(
+            Point3::new(0.0, 0.0, 30.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        );
+        wb.add_plane("Above", above);
+
+        wb
+    }
+
+    // Extrudes the given rectangle down from the plane above the block
+    fn add_rectangle_down(
+        wb: &mut Workbench,
+        low: (f64, f64),
+        high: (f64, f64),
+        length: f64,
+        mode: ExtrusionMode,
+        end: ExtrusionEnd,
+    ) {
+        let above_id = wb.plane_name_to_id("Above").unwrap();
+        let sketch_id = wb.add_sketch_to_plane("Rectangle", &above_id);
+        let s = wb.get_sketch_mut("Rectangle").unwrap();
+        let ll = s.add_point(low.0, low.1);
+        let lr = s.add_point(high.0, low.1);
+        let ul = s.add_point(low.0, high.1);
+        let ur = s.add_point(high.0, high.1);
+        s.add_segment(ll, lr);
+        s.add_segment(lr, ur);
+        s.add_segment(ur, ul);
+        s.add_segment(ul, ll);
+
+        let mut extrusion = Extrusion::new(
+            sketch_id,
+            vec![0],
+            length,
+            0.0,
+            Direction::NegativeNormal,
+            mode,
+        );
+        extrusion.end = end;
+        wb.add_extrusion("Rectangle1", extrusion);
+    }
+
+    // The block with the given rectangle cut into it to the given depth below its top face
+    fn cut_block(low: (f64, f64), high: (f64, f64), depth: f64) -> Realization {
+        let mut wb = block_workbench();
+        add_rectangle_down(
+            &mut wb,
+            low,
+            high,
+            depth + 5.0,
+            ExtrusionMode::Remove(vec!["Ext1:0".to_owned()]),
+            ExtrusionEnd::Blind,
+        );
+        wb.realize(1000)
+    }
+    #[test]
+    fn remove_through_cut() {
+        let realization = cut_block((15.0, 15.0), (25.0, 25.0), 35.0);
+        assert_eq!(realization.solids.len(), 1);
+
+        // nothing is left anywhere inside the square hole
+        let solid = &realization.solids["Ext1:0"];
+        assert_eq!(solid.truck_solid.boundaries().len(), 1);
+        assert!(!solid
+            .vertices
+            .iter()
+            .any(|v| v.x > 15.01 && v.x < 24.99 && v.y > 15.01 && v.y < 24.99));
+    }
+    #[test]
+    fn remove_blind_pocket() {
+        let realization = cut_block((15.0, 15.0), (25.0, 25.0), 10.0);
+        assert_eq!(realization.solids.len(), 1);
+
+        // the pocket has a floor 10 below the top, and nothing above it
+        let solid = &realization.solids["Ext1:0"];
+        let in_pocket = |v: &&Vector3| v.x > 15.01 && v.x < 24.99 && v.y > 15.01 && v.y < 24.99;
+        assert!(solid
+            .vertices
+            .iter()
+            .filter(in_pocket)
+            .all(|v| (v.z - 15.0).abs() < 1e-6));
+        assert!(solid.vertices.iter().filter(in_pocket).count() > 0);
+    }
+    #[test]
+    fn remove_splits_body() {
+        // a slot all the way across the block and all the way through it
+        let realization = cut_block((15.0, -5.0), (25.0, 45.0), 35.0);
+        assert_eq!(realization.solids.len(), 2);
+        assert!(realization.solids.contains_key("Ext1:0"));
+        assert!(realization.solids.contains_key("Ext1:0:1"));
+
+        for solid in realization.solids.values() {
+            assert_eq!(solid.truck_solid.boundaries().len(), 1);
+        }
+    }
+    #[test]
+    fn remove_through_all() {
+        let mut wb = block_workbench();
+        add_rectangle_down(
+            &mut wb,
+            (15.0, 15.0),
+            (25.0, 25.0),
+            1.0,
+            ExtrusionMode::Remove(vec!["Ext1:0".to_owned()]),
+            ExtrusionEnd::ThroughAll,
+        );
+
+        let realization = wb.realize(1000);
+        let solid = &realization.solids["Ext1:0"];
+        assert!(!solid
+            .vertices
+            .iter()
+            .any(|v| v.x > 15.01 && v.x < 24.99 && v.y > 15.01 && v.y < 24.99));
+    }
+    #[test]
+    fn remove_up_to_point() {
+        let mut wb = block_workbench();
+        wb.add_point("Floor", Point3::new(0.0, 0.0, 15.0));
+        add_rectangle_down(
+            &mut wb,
+            (15.0, 15.0),
+            (25.0, 25.0),
+            1.0,
+            ExtrusionMode::Remove(vec!["Ext1:0".to_owned()]),
+            ExtrusionEnd::UpToPoint {
+                point_id: "Point-1".to_owned(),
+            },
+        );
+
+        // the pocket floor is at the height of the point, however long the extrusion says it is
+        let realization = wb.realize(1000);
+        let solid = &realization.solids["Ext1:0"];
+        let in_pocket = |v: &&Vector3| v.x > 15.01 && v.x < 24.99 && v.y > 15.01 && v.y < 24.99;
+        assert!(solid.vertices.iter().filter(in_pocket).count() > 0);
+        assert!(solid
+            .vertices
+            .iter()
+            .filter(in_pocket)
+            .all(|v| (v.z - 15.0).abs() < 1e-6));
+    }
+    #[test]
+    fn through_all_with_nothing_ahead() {
+        let mut wb = square_workbench();
+        let mut extrusion = Extrusion::new(
+            "Sketch-0".to_owned(),
+            vec![0],
+            25.0,
+            0.0,
+            Direction::Normal,
+            ExtrusionMode::New,
+        );
+        extrusion.end = ExtrusionEnd::ThroughAll;
+        wb.add_extrusion("Ext1", extrusion.clone());
+
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 0);
+        let (_sketch, split_sketch, _name) = &realization.sketches["Sketch-0"];
+        let plane = &realization.planes[&split_sketch.plane_id];
+        assert!(matches!(
+            extrusion.extent(plane, split_sketch, &realization),
+            Err(CADmiumError::NothingToExtrudeThrough(_))
+        ));
+    }
+    #[test]
+    fn up_to_point_behind_sketch() {
+        let mut wb = block_workbench();
+        wb.add_point("Overhead", Point3::new(0.0, 0.0, 40.0));
+        add_rectangle_down(
+            &mut wb,
+            (15.0, 15.0),
+            (25.0, 25.0),
+            1.0,
+            ExtrusionMode::Remove(vec!["Ext1:0".to_owned()]),
+            ExtrusionEnd::UpToPoint {
+                point_id: "Point-1".to_owned(),
+            },
+        );
+
+        // the pocket goes down from the plane above, away from the point
+        let realization = wb.realize(1000);
+        let extrusion = match &wb.history.last().unwrap().data {
+            StepData::Extrusion { extrusion } => extrusion,
+            _ => panic!("the last step should be the pocket"),
+        };
+        let (_sketch, split_sketch, _name) = &realization.sketches[&extrusion.sketch_id];
+        let plane = &realization.planes[&split_sketch.plane_id];
+        assert!(matches!(
+            extrusion.extent(plane, split_sketch, &realization),
+            Err(CADmiumError::PointBehindExtrusion(_))
+        ));
+        assert!(!realization.solids["Ext1:0"]
+            .vertices
+            .iter()
+            .any(|v| v.x > 15.01 && v.x < 24.99 && v.y > 15.01 && v.y < 24.99));
+    }
+    #[test]
+    fn remove_up_to_derived_point() {
+        let mut wb = block_workbench();
+        let middle = wb.add_derived_point(
+            "Middle",
+            PointDescription::EdgeMidpoint {
+                solid_id: "Ext1:0".to_owned(),
+                near: Point3::new(41.0, -1.0, 10.0),
+            },
+        );
+        add_rectangle_down(
+            &mut wb,
+            (15.0, 15.0),
+            (25.0, 25.0),
+            1.0,
+            ExtrusionMode::Remove(vec!["Ext1:0".to_owned()]),
+            ExtrusionEnd::UpToPoint { point_id: middle },
+        );
+
+        // the floor is halfway up the block's corner edge
+        let realization = wb.realize(1000);
+        let solid = &realization.solids["Ext1:0"];
+        let in_pocket = |v: &&Vector3| v.x > 15.01 && v.x < 24.99 && v.y > 15.01 && v.y < 24.99;
+        assert!(solid.vertices.iter().filter(in_pocket).count() > 0);
+        assert!(solid
+            .vertices
+            .iter()
+            .filter(in_pocket)
+            .all(|v| (v.z - 12.5).abs() < 1e-6));
+    }
+    #[test]
+    fn add_up_to_next() {
+        let mut wb = block_workbench();
+        add_rectangle_down(
+            &mut wb,
+            (15.0, 15.0),
+            (25.0, 25.0),
+            1.0,
+            ExtrusionMode::Add(vec!["Ext1:0".to_owned()]),
+            ExtrusionEnd::UpToNext,
+        );
+
+        // a post from the plane above down to the block's top face
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 1);
+        let solid = &realization.solids["Ext1:0"];
+        let top = solid.vertices.iter().map(|v| v.z).fold(f64::MIN, f64::max);
+        assert!((top - 30.0).abs() < 1e-6);
+    }
+    #[test]
+    fn add_bridge_across_two_pads() {
+        // two separate 10x10x10 pads, 20 apart
+        let mut wb = Workbench::new("Test Workbench");
+        wb.add_sketch_to_plane("Pads", "Plane-2");
+        let s = wb.get_sketch_mut("Pads").unwrap();
+        for x in [0.0, 30.0] {
+            let ll = s.add_point(x, 0.0);
+            let lr = s.add_point(x + 10.0, 0.0);
+            let ul = s.add_point(x, 10.0);
+            let ur = s.add_point(x + 10.0, 10.0);
+            s.add_segment(ll, lr);
+            s.add_segment(lr, ur);
+            s.add_segment(ur, ul);
+            s.add_segment(ul, ll);
+        }
+        let extrusion = Extrusion::new(
+            "Sketch-0".to_owned(),
+            vec![0, 1],
+            10.0,
+            0.0,
+            Direction::Normal,
+            ExtrusionMode::New,
+        );
+        wb.add_extrusion("Pads", extrusion);
+
+        // a deck on top of both of them, overhanging each on every side
+        let deck = Plane::new(
+            Point3::new(0.0, 0.0, 10.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        );
+        let deck_id = wb.add_plane("Deck", deck);
+        let sketch_id = wb.add_sketch_to_plane("Bridge", &deck_id);
+        let s = wb.get_sketch_mut("Bridge").unwrap();
+        let ll = s.add_point(-5.0, -5.0);
+        let lr = s.add_point(45.0, -5.0);
+        let ul = s.add_point(-5.0, 15.0);
+        let ur = s.add_point(45.0, 15.0);
+        s.add_segment(ll, lr);
+        s.add_segment(lr, ur);
+        s.add_segment(ur, ul);
+        s.add_segment(ul, ll);
+        let extrusion = Extrusion::new(
+            sketch_id,
+            vec![0],
+            5.0,
+            0.0,
+            Direction::Normal,
+            ExtrusionMode::Add(vec!["Pads:0".to_owned(), "Pads:1".to_owned()]),
+        );
+        wb.add_extrusion("Bridge", extrusion);
+
+        // the bridge joins both pads into one body, and only once
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 1);
+        let solid = &realization.solids["Pads:0"];
+        assert_eq!(solid.truck_solid.boundaries().len(), 1);
+        let top = solid.vertices.iter().map(|v| v.z).fold(f64::MIN, f64::max);
+        assert!((top - 15.0).abs() < 1e-6);
+        assert!(solid.vertices.iter().any(|v| v.x < 1e-6 && v.z < 1e-6));
+        assert!(solid
+            .vertices
+            .iter()
+            .any(|v| v.x > 40.0 - 1e-6 && v.z < 1e-6));
+    }
+    #[test]
+    fn intersect_two_profiles() {
+        let mut wb = block_workbench();
+
+        // a side profile that pokes out past the block in every direction but x
+        let sketch_id = wb.add_sketch_to_plane("Side", "Plane-0");
+        let s = wb.get_sketch_mut("Side").unwrap();
+        let ll = s.add_point(10.0, -5.0);
+        let lr = s.add_point(30.0, -5.0);
+        let ul = s.add_point(10.0, 15.0);
+        let ur = s.add_point(30.0, 15.0);
+        s.add_segment(ll, lr);
+        s.add_segment(lr, ur);
+        s.add_segment(ur, ul);
+        s.add_segment(ul, ll);
+
+        let extrusion = Extrusion::new(
+            sketch_id,
+            vec![0],
+            45.0,
+            -5.0,
+            Direction::NegativeNormal,
+            ExtrusionMode::Intersect(vec!["Ext1:0".to_owned()]),
+        );
+        wb.add_extrusion("Ext2", extrusion);
+
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 1);
+
+        let solid = &realization.solids["Ext1:0"];
+        for v in solid.vertices.iter() {
+            assert!(v.x > 10.0 - 1e-6 && v.x < 30.0 + 1e-6);
+            assert!(v.y > -1e-6 && v.y < 40.0 + 1e-6);
+            assert!(v.z > -1e-6 && v.z < 15.0 + 1e-6);
+        }
+    }
+    #[test]
+    fn make_workbench_with_two_sided_extrusion() {
+        let mut wb = square_workbench();
+
+        let mut extrusion = Extrusion::new(
+            "Sketch-0".to_owned(),
+            vec![0],
+            25.0,
+            0.0,
+            Direction::Normal,
+            ExtrusionMode::New,
+        );
+        extrusion.second_length = 10.0;
+        wb.add_extrusion("Ext1", extrusion);
+
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 1);
+
+        let solid = &realization.solids["Ext1:0"];
+        assert_eq!(solid.truck_solid.boundaries().len(), 1);
+        let bottom = solid.vertices.iter().map(|v| v.z).fold(f64::MAX, f64::min);
+        let top = solid.vertices.iter().map(|v| v.z).fold(f64::MIN, f64::max);
+        assert!((bottom + 10.0).abs() < 1e-6);
+        assert!((top - 25.0).abs() < 1e-6);
+    }
+    #[test]
+    fn make_workbench_with_tapered_extrusion() {
+        let mut wb = square_workbench();
+
+        let mut extrusion = Extrusion::new(
+            "Sketch-0".to_owned(),
+            vec![0],
+            25.0,
+            0.0,
+            Direction::Normal,
+            ExtrusionMode::New,
+        );
+        extrusion.draft_angle = PI / 36.0;
+        wb.add_extrusion("Ext1", extrusion);
+
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 1);
+
+        // the top of the block is pulled in on every side
+        let inset = 25.0 * (PI / 36.0).tan();
+        let solid = &realization.solids["Ext1:0"];
+        for vertex in solid.vertices.iter().filter(|v| (v.z - 25.0).abs() < 1e-6) {
+            assert!(vertex.x > inset - 1e-6 && vertex.x < 40.0 - inset + 1e-6);
+            assert!(vertex.y > inset - 1e-6 && vertex.y < 40.0 - inset + 1e-6);
+        }
+    }
+    #[test]
+    fn tapered_extrusion_too_steep() {
+        let mut wb = square_workbench();
+
+        // 25 long at this angle, each side would be pulled in by more than half the square
+        let mut extrusion = Extrusion::new(
+            "Sketch-0".to_owned(),
+            vec![0],
+            25.0,
+            0.0,
+            Direction::Normal,
+            ExtrusionMode::New,
+        );
+        extrusion.draft_angle = PI / 3.0;
+        wb.add_extrusion("Ext1", extrusion);
+
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 0);
+    }
+    #[test]
+    fn tapered_extrusion_along_long_direction() {
+        let mut wb = square_workbench();
+
+        // a direction twice the unit length doubles the distance, and the lean with it
+        let mut extrusion = Extrusion::new(
+            "Sketch-0".to_owned(),
+            vec![0],
+            10.0,
+            0.0,
+            Direction::Specified(Vector3::new(0.0, 0.0, 2.0)),
+            ExtrusionMode::New,
+        );
+        extrusion.draft_angle = PI / 36.0;
+        wb.add_extrusion("Ext1", extrusion);
+
+        let realization = wb.realize(1000);
+        let inset = 20.0 * (PI / 36.0).tan();
+        let solid = &realization.solids["Ext1:0"];
+        let top: Vec<&Vector3> = solid
+            .vertices
+            .iter()
+            .filter(|v| (v.z - 20.0).abs() < 1e-6)
+            .collect();
+        assert!(!top.is_empty());
+        for vertex in top {
+            assert!(vertex.x > inset - 1e-6 && vertex.x < 40.0 - inset + 1e-6);
+        }
+        assert!(solid
+            .vertices
+            .iter()
+            .any(|v| (v.z - 20.0).abs() < 1e-6 && (v.x - inset).abs() < 1e-6));
+    }
+    #[test]
+    fn make_workbench_with_draft() {
+        let mut p = create_test_project();
+        let wb = p.workbenches.get_mut(0).unwrap();
+
+        // tilt the right side of the block, hinged where it meets the Front plane
+        let draft = Draft::new(
+            "Ext1:0".to_owned(),
+            vec![Vector3::new(1.0, 0.0, 0.0)],
+            "Plane-0".to_owned(),
+            PI / 60.0,
+        );
+        wb.add_draft("Draft1", draft);
+
+        let before = wb.realize(wb.history.len() as u64 - 1);
+        let after = wb.realize(1000);
+        assert_eq!(after.solids.len(), 1);
+        assert_ne!(before.solids["Ext1:0"].crc32, after.solids["Ext1:0"].crc32);
+    }
+    #[test]
+    fn draft_outward_stays_within_face() {
+        let mut p = create_test_project();
+        let wb = p.workbenches.get_mut(0).unwrap();
+
+        // flare the right side of the block out instead of in
+        let draft = Draft::new(
+            "Ext1:0".to_owned(),
+            vec![Vector3::new(1.0, 0.0, 0.0)],
+            "Plane-0".to_owned(),
+            -PI / 60.0,
+        );
+        wb.add_draft("Draft1", draft);
+
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 1);
+
+        // the added wedge leans out past x = 40, but no further along the face's hinge
+        // than the face itself went
+        let flare = 25.0 * (PI / 60.0).tan();
+        let solid = &realization.solids["Ext1:0"];
+        assert!(solid.vertices.iter().any(|v| v.x > 40.0 + 1e-3));
+        for v in solid.vertices.iter() {
+            assert!(v.x > -1e-6 && v.x < 40.0 + flare + 1e-6);
+            assert!(v.y > -25.0 - 1e-6 && v.y < 1e-6);
+            assert!(v.z > -1e-6 && v.z < 40.0 + 1e-6);
+        }
+    }
+    #[test]
+    fn make_workbench_with_shell() {
+        let mut wb = block_workbench();
+
+        // an open topped box with 2 unit walls
+        let shell = Shell::new("Ext1:0".to_owned(), 2.0, vec![Vector3::new(0.0, 0.0, 1.0)]);
+        wb.add_shell("Shell1", shell);
+
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 1);
+
+        // the floor is 2 thick and the walls are the only thing left at the top
+        let solid = &realization.solids["Ext1:0"];
+        let in_cavity = |v: &&Vector3| v.x > 2.01 && v.x < 37.99 && v.y > 2.01 && v.y < 37.99;
+        assert!(solid.vertices.iter().filter(in_cavity).count() > 0);
+        assert!(solid
+            .vertices
+            .iter()
+            .filter(in_cavity)
+            .all(|v| (v.z - 2.0).abs() < 1e-6));
+    }
+    #[test]
+    fn shell_pocketed_block() {
+        // a 10x10 pocket 10 deep in the middle of the block's top, then hollowed with the
+        // top left open
+        let mut wb = block_workbench();
+        add_rectangle_down(
+            &mut wb,
+            (15.0, 15.0),
+            (25.0, 25.0),
+            15.0,
+            ExtrusionMode::Remove(vec!["Ext1:0".to_owned()]),
+            ExtrusionEnd::Blind,
+        );
+        let shell = Shell::new("Ext1:0".to_owned(), 2.0, vec![Vector3::new(0.0, 0.0, 1.0)]);
+        wb.add_shell("Shell1", shell);
+
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 1);
+
+        // between the outer wall and the wall around the pocket there is only the floor
+        let solid = &realization.solids["Ext1:0"];
+        let in_cavity = |v: &&Vector3| v.x > 2.01 && v.x < 12.99 && v.y > 2.01 && v.y < 37.99;
+        assert!(solid.vertices.iter().filter(in_cavity).count() > 0);
+        assert!(solid
+            .vertices
+            .iter()
+            .filter(in_cavity)
+            .all(|v| (v.z - 2.0).abs() < 1e-6));
+    }
+    #[test]
+    fn make_workbench_with_linear_pattern() {
+        let mut wb = block_workbench();
+        add_rectangle_down(
+            &mut wb,
+            (4.0, 4.0),
+            (8.0, 8.0),
+            15.0,
+            ExtrusionMode::Remove(vec!["Ext1:0".to_owned()]),
+            ExtrusionEnd::Blind,
+        );
+
+        // a 3 by 2 grid of pockets, each with its floor 10 below the top
+        let first = PatternDirection {
+            direction: Vector3::new(1.0, 0.0, 0.0),
+            count: 3,
+            spacing: 10.0,
+        };
+        let second = PatternDirection {
+            direction: Vector3::new(0.0, 1.0, 0.0),
+            count: 2,
+            spacing: 20.0,
+        };
+        let pattern = LinearPattern::new(
+            PatternSource::Step("Extrusion-1".to_owned()),
+            first,
+            Some(second),
+        );
+        wb.add_linear_pattern("Pattern1", pattern);
+
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 1);
+
+        let solid = &realization.solids["Ext1:0"];
+        for i in 0..3 {
+            for j in 0..2 {
+                let (x, y) = (4.0 + 10.0 * i as f64, 4.0 + 20.0 * j as f64);
+                let in_pocket = |v: &&Vector3| {
+                    v.x > x + 0.01 && v.x < x + 3.99 && v.y > y + 0.01 && v.y < y + 3.99
+                };
+                assert!(solid
+                    .vertices
+                    .iter()
+                    .filter(in_pocket)
+                    .all(|v| (v.z - 15.0).abs() < 1e-6));
+                assert!(solid.vertices.iter().filter(in_pocket).count() > 0);
+            }
+        }
+    }
+    #[test]
+    fn make_workbench_with_circular_pattern() {
+        let mut wb = block_workbench();
+        wb.add_point("Center", Point3::new(20.0, 20.0, 0.0));
+        add_rectangle_down(
+            &mut wb,
+            (4.0, 18.0),
+            (8.0, 22.0),
+            15.0,
+            ExtrusionMode::Remove(vec!["Ext1:0".to_owned()]),
+            ExtrusionEnd::Blind,
+        );
+
+        // four pockets spread around the middle of the block
+        let axis = PatternAxis::PlaneNormal {
+            plane_id: "Plane-2".to_owned(),
+            point_id: Some("Point-1".to_owned()),
+        };
+        let pattern = CircularPattern::new(
+            PatternSource::Step("Extrusion-1".to_owned()),
+            axis,
+            4,
+            2.0 * PI,
+            true,
+        );
+        wb.add_circular_pattern("Pattern1", pattern);
+
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 1);
+
+        let solid = &realization.solids["Ext1:0"];
+        for (x, y) in [(4.0, 18.0), (18.0, 4.0), (32.0, 18.0), (18.0, 32.0)] {
+            let in_pocket =
+                |v: &&Vector3| v.x > x + 0.01 && v.x < x + 3.99 && v.y > y + 0.01 && v.y < y + 3.99;
+            assert!(solid
+                .vertices
+                .iter()
+                .filter(in_pocket)
+                .all(|v| (v.z - 15.0).abs() < 1e-6));
+            assert!(solid.vertices.iter().filter(in_pocket).count() > 0);
+        }
+    }
+    #[test]
+    fn mirror_pocket_across_plane() {
+        let mut wb = block_workbench();
+        let middle = Plane::new(
+            Point3::new(20.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(1.0, 0.0, 0.0),
+        );
+        let middle_id = wb.add_plane("Middle", middle);
+        add_rectangle_down(
+            &mut wb,
+            (4.0, 4.0),
+            (8.0, 8.0),
+            15.0,
+            ExtrusionMode::Remove(vec!["Ext1:0".to_owned()]),
+            ExtrusionEnd::Blind,
+        );
+
+        let mirror = Mirror::new(
+            vec![PatternSource::Step("Extrusion-1".to_owned())],
+            MirrorPlane::Plane(middle_id),
+            false,
+        );
+        wb.add_mirror("Mirror1", mirror);
+
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 1);
+
+        // the pocket shows up again on the far side of the block
+        let solid = &realization.solids["Ext1:0"];
+        for x in [4.0, 32.0] {
+            let in_pocket =
+                |v: &&Vector3| v.x > x + 0.01 && v.x < x + 3.99 && v.y > 4.01 && v.y < 7.99;
+            assert!(solid
+                .vertices
+                .iter()
+                .filter(in_pocket)
+                .all(|v| (v.z - 15.0).abs() < 1e-6));
+            assert!(solid.vertices.iter().filter(in_pocket).count() > 0);
+        }
+    }
+    #[test]
+    fn mirror_solid_across_face() {
+        let mut wb = block_workbench();
+        let mirror = Mirror::new(
+            vec![PatternSource::Solid("Ext1:0".to_owned())],
+            MirrorPlane::SolidFace {
+                solid_id: "Ext1:0".to_owned(),
+                normal: Vector3::new(1.0, 0.0, 0.0),
+                near: None,
+            },
+            true,
+        );
+        wb.add_mirror("Mirror1", mirror);
+
+        // the block and its reflection fuse into one block twice as long
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 1);
+        let solid = &realization.solids["Ext1:0"];
+        let longest = solid.vertices.iter().map(|v| v.x).fold(f64::MIN, f64::max);
+        assert!((longest - 80.0).abs() < 1e-6);
+    }
+    #[test]
+    fn mirror_across_stepped_top() {
+        // a post on the block, so the block's top and the post's top both face up
+        let post_workbench = || {
+            let mut wb = block_workbench();
+            add_rectangle_down(
+                &mut wb,
+                (15.0, 15.0),
+                (25.0, 25.0),
+                1.0,
+                ExtrusionMode::Add(vec!["Ext1:0".to_owned()]),
+                ExtrusionEnd::UpToNext,
+            );
+            wb
+        };
+        let top_of = |near: Option<Point3>| {
+            Mirror::new(
+                vec![PatternSource::Solid("Ext1:0".to_owned())],
+                MirrorPlane::SolidFace {
+                    solid_id: "Ext1:0".to_owned(),
+                    normal: Vector3::new(0.0, 0.0, 1.0),
+                    near,
+                },
+                false,
+            )
+        };
+
+        // without near the mirror can't tell the two tops apart, and leaves the block be
+        let mut wb = post_workbench();
+        wb.add_mirror("Mirror1", top_of(None));
+        assert_eq!(wb.realize(1000).solids.len(), 1);
+
+        // near the post's top, the copy is flipped over the top of the post at z = 30
+        let mut wb = post_workbench();
+        wb.add_mirror("Mirror1", top_of(Some(Point3::new(20.0, 20.0, 30.0))));
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 2);
+        let copy = &realization.solids["Mirror1:0"];
+        let lowest = copy.vertices.iter().map(|v| v.z).fold(f64::MAX, f64::min);
+        let highest = copy.vertices.iter().map(|v| v.z).fold(f64::MIN, f64::max);
+        assert!((lowest - 30.0).abs() < 1e-6);
+        assert!((highest - 60.0).abs() < 1e-6);
+    }
+    #[test]
+    fn make_workbench_with_sweep() {
+        let mut wb = Workbench::new("Test Workbench");
+
+        // a 2 by 2 square standing on the front plane
+        let profile_id = wb.add_sketch_to_plane("Profile", "Plane-0");
+        let s = wb.get_sketch_mut("Profile").unwrap();
+        let ll = s.add_point(-1.0, -1.0);
+        let lr = s.add_point(1.0, -1.0);
+        let ul = s.add_point(-1.0, 1.0);
+        let ur = s.add_point(1.0, 1.0);
+        s.add_segment(ll, lr);
+        s.add_segment(lr, ur);
+        s.add_segment(ur, ul);
+        s.add_segment(ul, ll);
+
+        // a path that runs straight along y, then bends a quarter turn toward x
+        let path_id = wb.add_sketch_to_plane("Path", "Plane-2");
+        let s = wb.get_sketch_mut("Path").unwrap();
+        let origin = s.add_point(0.0, 0.0);
+        let bend = s.add_point(0.0, 20.0);
+        let center = s.add_point(10.0, 20.0);
+        let finish = s.add_point(10.0, 30.0);
+        let line_id = s.add_segment(origin, bend);
+        let arc_id = s.add_arc(center, bend, finish, true);
+
+        let sweep = Sweep::new(
+            profile_id,
+            vec![0],
+            path_id,
+            vec![PathSegment::Line(line_id), PathSegment::Arc(arc_id)],
+            SweepOrientation::FollowPath,
+            ExtrusionMode::New,
+        );
+        wb.add_sweep("Sweep1", sweep);
+
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 1);
+
+        // the far end of the bend is a square facing along x
+        let solid = &realization.solids["Sweep1:0"];
+        let furthest = solid.vertices.iter().map(|v| v.x).fold(f64::MIN, f64::max);
+        assert!((furthest - 10.0).abs() < 1e-6);
+        let end_cap: Vec<&Vector3> = solid
+            .vertices
+            .iter()
+            .filter(|v| (v.x - 10.0).abs() < 1e-6)
+            .collect();
+        assert!(end_cap
+            .iter()
+            .all(|v| v.y > 28.99 && v.y < 31.01 && v.z > -1.01 && v.z < 1.01));
+    }
+    #[test]
+    fn make_workbench_with_loft() {
+        let mut wb = Workbench::new("Test Workbench");
+
+        // a 10 by 10 square on the ground
+        let square_id = wb.add_sketch_to_plane("Square", "Plane-2");
+        let s = wb.get_sketch_mut("Square").unwrap();
+        let ll = s.add_point(-5.0, -5.0);
+        let lr = s.add_point(5.0, -5.0);
+        let ul = s.add_point(-5.0, 5.0);
+        let ur = s.add_point(5.0, 5.0);
+        s.add_segment(ll, lr);
+        s.add_segment(lr, ur);
+        s.add_segment(ur, ul);
+        s.add_segment(ul, ll);
+
+        // a circle of radius 4, 20 above it
+        let above = Plane::new(
+            Point3::new(0.0, 0.0, 20.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        );
+        let above_id = wb.add_plane("Above", above);
+        let circle_id = wb.add_sketch_to_plane("Circle", &above_id);
+        let s = wb.get_sketch_mut("Circle").unwrap();
+        let center = s.add_point(0.0, 0.0);
+        s.add_circle(center, 4.0);
+
+        let profile = |sketch_id: String| LoftProfile {
+            sketch_id,
+            face_id: 0,
+            start_point: None,
+        };
+        let loft = Loft::new(
+            vec![profile(square_id), profile(circle_id)],
+            ExtrusionMode::New,
+        );
+        wb.add_loft("Loft1", loft);
+
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 1);
+
+        // square at the bottom, round at the top
+        let solid = &realization.solids["Loft1:0"];
+        let top: Vec<&Vector3> = solid
+            .vertices
+            .iter()
+            .filter(|v| (v.z - 20.0).abs() < 1e-6)
+            .collect();
+        assert!(!top.is_empty());
+        assert!(top
+            .iter()
+            .all(|v| ((v.x * v.x + v.y * v.y).sqrt() - 4.0).abs() < 0.1));
+        assert!(solid
+            .vertices
+            .iter()
+            .all(|v| v.z > -1e-6 && v.z < 20.0 + 1e-6 && v.x.abs() < 5.0 + 1e-6));
+        assert!(solid
+            .vertices
+            .iter()
+            .any(|v| v.z.abs() < 1e-6 && (v.x - 5.0).abs() < 1e-6 && (v.y - 5.0).abs() < 1e-6));
+    }
+    #[test]
+    fn make_workbench_with_thin_wall() {
+        let mut wb = Workbench::new("Test Workbench");
+
+        // an open L, which has no faces at all
+        let sketch_id = wb.add_sketch_to_plane("Sketch 1", "Plane-2");
+        let s = wb.get_sketch_mut("Sketch 1").unwrap();
+        let a = s.add_point(0.0, 0.0);
+        let b = s.add_point(20.0, 0.0);
+        let c = s.add_point(20.0, 10.0);
+        s.add_segment(a, b);
+        let bc = s.add_segment(b, c);
+
+        let mut extrusion = Extrusion::new(
+            sketch_id,
+            vec![],
+            5.0,
+            0.0,
+            Direction::Normal,
+            ExtrusionMode::New,
+        );
+        extrusion.thin = Some(ThinWall::new(
+            vec![PathSegment::Line(bc)],
+            2.0,
+            WallSide::MidPlane,
+        ));
+        wb.add_extrusion("Wall1", extrusion);
+
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 1);
+
+        // a wall 2 thick centered on the L, with mitered corners
+        let solid = &realization.solids["Wall1:0"];
+        assert!(solid.vertices.iter().all(|v| v.x > -1e-6
+            && v.x < 21.0 + 1e-6
+            && v.y > -1.0 - 1e-6
+            && v.y < 10.0 + 1e-6
+            && v.z > -1e-6
+            && v.z < 5.0 + 1e-6));
+        let has = |x: f64, y: f64| {
+            solid
+                .vertices
+                .iter()
+                .any(|v| (v.x - x).abs() < 1e-6 && (v.y - y).abs() < 1e-6)
+        };
+        assert!(has(21.0, -1.0));
+        assert!(has(19.0, 1.0));
+        assert!(has(0.0, 1.0));
+        assert!(has(19.0, 10.0));
+    }
+    #[test]
+    fn thin_wall_after_deleted_line() {
+        let mut wb = Workbench::new("Test Workbench");
+
+        // a line drawn and deleted again leaves a gap in the ids
+        let sketch_id = wb.add_sketch_to_plane("Sketch 1", "Plane-2");
+        let s = wb.get_sketch_mut("Sketch 1").unwrap();
+        let a = s.add_point(0.0, 0.0);
+        let b = s.add_point(20.0, 0.0);
+        let c = s.add_point(20.0, 10.0);
+        let stray = s.add_segment(a, c);
+        s.delete_line_segment(stray);
+        s.add_segment(a, b);
+        let bc = s.add_segment(b, c);
+
+        let mut extrusion = Extrusion::new(
+            sketch_id,
+            vec![],
+            5.0,
+            0.0,
+            Direction::Normal,
+            ExtrusionMode::New,
+        );
+        extrusion.thin = Some(ThinWall::new(
+            vec![PathSegment::Line(bc)],
+            2.0,
+            WallSide::MidPlane,
+        ));
+        wb.add_extrusion("Wall1", extrusion);
+
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 1);
+        let solid = &realization.solids["Wall1:0"];
+        let has = |x: f64, y: f64| {
+            solid
+                .vertices
+                .iter()
+                .any(|v| (v.x - x).abs() < 1e-6 && (v.y - y).abs() < 1e-6)
+        };
+        assert!(has(21.0, -1.0));
+        assert!(has(19.0, 10.0));
+    }
+    #[test]
+    fn thin_wall_too_thick_for_arc() {
+        let wall_on_arc = |thickness: f64| {
+            let mut wb = Workbench::new("Test Workbench");
+            let sketch_id = wb.add_sketch_to_plane("Sketch 1", "Plane-2");
+            let s = wb.get_sketch_mut("Sketch 1").unwrap();
+            let center = s.add_point(0.0, 0.0);
+            let start = s.add_point(5.0, 0.0);
+            let end = s.add_point(0.0, 5.0);
+            let arc = s.add_arc(center, start, end, false);
+
+            let mut extrusion = Extrusion::new(
+                sketch_id,
+                vec![],
+                5.0,
+                0.0,
+                Direction::Normal,
+                ExtrusionMode::New,
+            );
+            extrusion.thin = Some(ThinWall::new(
+                vec![PathSegment::Arc(arc)],
+                thickness,
+                WallSide::MidPlane,
+            ));
+            wb.add_extrusion("Wall1", extrusion);
+            wb.realize(1000)
+        };
+
+        assert_eq!(wall_on_arc(2.0).solids.len(), 1);
+
+        // half of the wall would have to fit inside the arc's radius of 5
+        assert_eq!(wall_on_arc(12.0).solids.len(), 0);
+    }
+    #[test]
+    fn make_workbench_with_rib() {
+        let wb = shelled_box_with_rib(Direction::NegativeNormal);
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 1);
+
+        // the rib runs from wall to wall and down to the floor
+        let solid = &realization.solids["Ext1:0"];
+        let has = |x: f64, y: f64, z: f64| {
+            solid
+                .vertices
+                .iter()
+                .any(|v| (v.x - x).abs() < 1e-6 && (v.y - y).abs() < 1e-6 && (v.z - z).abs() < 1e-6)
+        };
+        assert!(has(2.0, 19.0, 20.0));
+        assert!(has(38.0, 21.0, 20.0));
+        assert!(has(2.0, 19.0, 2.0));
+        assert!(has(38.0, 21.0, 2.0));
+    }
+    #[test]
+    fn rib_out_of_open_top() {
+        // growing up, the rib would leave through the open top without meeting anything,
+        // so the box is left as it was
+        let wb = shelled_box_with_rib(Direction::Normal);
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 1);
+        let solid = &realization.solids["Ext1:0"];
+        assert!(!solid
+            .vertices
+            .iter()
+            .any(|v| (v.y - 19.0).abs() < 1e-6 && (v.z - 20.0).abs() < 1e-6));
+    }
+
+    // The block with its top shelled away, and a rib along a short line across the inside
+    // of the box, growing from 5 below its rim
+    fn shelled_box_with_rib(direction: Direction) -> Workbench {
+        let mut wb = block_workbench();
+        let shell = Shell::new("Ext1:0".to_owned(), 2.0, vec![Vector3::new(0.0, 0.0, 1.0)]);
+        wb.add_shell("Shell1", shell);
+
+        let rib_plane = Plane::new(
+            Point3::new(0.0, 0.0, 20.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        );
+        let rib_plane_id = wb.add_plane("Rib Plane", rib_plane);
+        let sketch_id = wb.add_sketch_to_plane("Rib Sketch", &rib_plane_id);
+        let s = wb.get_sketch_mut("Rib Sketch").unwrap();
+        let start = s.add_point(10.0, 20.0);
+        let end = s.add_point(30.0, 20.0);
+        let line = s.add_segment(start, end);
+
+        let rib = Rib::new(
+            sketch_id,
+            PathSegment::Line(line),
+            "Ext1:0".to_owned(),
+            2.0,
+            WallSide::MidPlane,
+            direction,
+        );
+        wb.add_rib("Rib1", rib);
+        wb
+    }
+    #[test]
+    fn split_block_with_plane() {
+        let mut wb = block_workbench();
+        let cut = Plane::new(
+            Point3::new(15.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(1.0, 0.0, 0.0),
+        );
+        let cut_id = wb.add_plane("Cut", cut);
+        let split = Split::new(
+            "Ext1:0".to_owned(),
+            SplitTool::Plane(cut_id),
+            SplitKeep::Both,
+        );
+        wb.add_split("Split1", split);
+
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 2);
+        assert!(!realization.solids.contains_key("Ext1:0"));
+
+        // the front piece is on the side the plane's normal points to
+        let front = &realization.solids["Split1:0"];
+        let back = &realization.solids["Split1:1"];
+        assert!(front.vertices.iter().all(|v| v.x > 15.0 - 1e-6));
+        assert!(back.vertices.iter().all(|v| v.x < 15.0 + 1e-6));
+        assert!(front.vertices.iter().any(|v| (v.x - 40.0).abs() < 1e-6));
+        assert!(back.vertices.iter().any(|v| v.x.abs() < 1e-6));
+    }
+
+    #[test]
+    fn split_block_with_sketch_chain() {
+        let mut wb = block_workbench();
+
+        // a bent line across the top of the block, from the front edge to the right one
+        let above_id = wb.plane_name_to_id("Above").unwrap();
+        let sketch_id = wb.add_sketch_to_plane("Cut", &above_id);
+        let s = wb.get_sketch_mut("Cut").unwrap();
+        let start = s.add_point(10.0, 0.0);
+        let corner = s.add_point(10.0, 30.0);
+        let end = s.add_point(40.0, 30.0);
+        let first = s.add_segment(start, corner);
+        s.add_segment(corner, end);
+
+        let tool = SplitTool::Chain {
+            sketch_id,
+            segment: PathSegment::Line(first),
+        };
+        wb.add_split(
+            "Split1",
+            Split::new("Ext1:0".to_owned(), tool, SplitKeep::Back),
+        );
+
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 1);
+
+        // only the corner to the right of the line is kept
+        let back = &realization.solids["Split1:1"];
+        assert!(back
+            .vertices
+            .iter()
+            .all(|v| v.x > 10.0 - 1e-6 && v.y < 30.0 + 1e-6));
+        assert!(back
+            .vertices
+            .iter()
+            .any(|v| (v.x - 40.0).abs() < 1e-6 && v.y.abs() < 1e-6));
+    }
+
+    #[test]
+    fn split_block_with_chain_bending_left() {
+        let mut wb = block_workbench();
+
+        // up from the front edge, then left out to the other side. The front side is the
+        // inside of the bend
+        let above_id = wb.plane_name_to_id("Above").unwrap();
+        let sketch_id = wb.add_sketch_to_plane("Cut", &above_id);
+        let s = wb.get_sketch_mut("Cut").unwrap();
+        let start = s.add_point(10.0, 0.0);
+        let corner = s.add_point(10.0, 30.0);
+        let end = s.add_point(0.0, 30.0);
+        let first = s.add_segment(start, corner);
+        s.add_segment(corner, end);
+
+        let tool = SplitTool::Chain {
+            sketch_id,
+            segment: PathSegment::Line(first),
+        };
+        wb.add_split(
+            "Split1",
+            Split::new("Ext1:0".to_owned(), tool, SplitKeep::Both),
+        );
+
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 2);
+        let front = &realization.solids["Split1:0"];
+        let back = &realization.solids["Split1:1"];
+        assert!(front
+            .vertices
+            .iter()
+            .all(|v| v.x < 10.0 + 1e-6 && v.y < 30.0 + 1e-6));
+        assert!(back
+            .vertices
+            .iter()
+            .any(|v| (v.x - 40.0).abs() < 1e-6 && (v.y - 40.0).abs() < 1e-6));
+    }
+
+    #[test]
+    fn split_u_shape_with_plane() {
+        // a slot down the middle of the block leaves two prongs standing above z=10
+        let mut wb = block_workbench();
+        add_rectangle_down(
+            &mut wb,
+            (15.0, -5.0),
+            (25.0, 45.0),
+            20.0,
+            ExtrusionMode::Remove(vec!["Ext1:0".to_owned()]),
+            ExtrusionEnd::Blind,
+        );
+        let cut = Plane::new(
+            Point3::new(0.0, 0.0, 15.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        );
+        let cut_id = wb.add_plane("Cut", cut);
+        let split = Split::new(
+            "Ext1:0".to_owned(),
+            SplitTool::Plane(cut_id),
+            SplitKeep::Both,
+        );
+        wb.add_split("Split1", split);
+
+        // the two prong tips come off as separate bodies
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 3);
+        for name in ["Split1:0", "Split1:0:1", "Split1:1"] {
+            let solid = &realization.solids[name];
+            assert_eq!(solid.truck_solid.boundaries().len(), 1);
+        }
+        let tips = [
+            &realization.solids["Split1:0"],
+            &realization.solids["Split1:0:1"],
+        ];
+        for tip in tips {
+            assert!(tip.vertices.iter().all(|v| v.z > 15.0 - 1e-6));
+            assert!(
+                tip.vertices.iter().all(|v| v.x < 15.0 + 1e-6)
+                    || tip.vertices.iter().all(|v| v.x > 25.0 - 1e-6)
+            );
+        }
+    }
+
+    #[test]
+    fn make_workbench_with_booleans() {
+        // a post that sinks 10 into the top of the block
+        let post_workbench = || {
+            let mut wb = block_workbench();
+            add_rectangle_down(
+                &mut wb,
+                (10.0, 10.0),
+                (30.0, 30.0),
+                15.0,
+                ExtrusionMode::New,
+                ExtrusionEnd::Blind,
+            );
+            wb
+        };
+        let has = |solid: &Solid, x: f64, y: f64, z: f64| {
+            solid
+                .vertices
+                .iter()
+                .any(|v| (v.x - x).abs() < 1e-6 && (v.y - y).abs() < 1e-6 && (v.z - z).abs() < 1e-6)
+        };
+
+        // the post is used up cutting a pocket into the block
+        let mut wb = post_workbench();
+        let subtract = Boolean::new(
+            "Ext1:0".to_owned(),
+            vec!["Rectangle1:0".to_owned()],
+            BooleanOperation::Subtract,
+            false,
+        );
+        wb.add_boolean("Boolean1", subtract);
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 1);
+        assert!(has(&realization.solids["Ext1:0"], 10.0, 10.0, 15.0));
+
+        // the post stays around after the block has been cut down to their overlap
+        let mut wb = post_workbench();
+        let intersect = Boolean::new(
+            "Ext1:0".to_owned(),
+            vec!["Rectangle1:0".to_owned()],
+            BooleanOperation::Intersect,
+            true,
+        );
+        wb.add_boolean("Boolean1", intersect);
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 2);
+        let overlap = &realization.solids["Ext1:0"];
+        assert!(overlap
+            .vertices
+            .iter()
+            .all(|v| v.z > 15.0 - 1e-6 && v.x > 10.0 - 1e-6 && v.x < 30.0 + 1e-6));
+        assert!(has(&realization.solids["Rectangle1:0"], 10.0, 10.0, 30.0));
+
+        // joining them leaves a single solid that keeps the block's name
+        let mut wb = post_workbench();
+        let union = Boolean::new(
+            "Ext1:0".to_owned(),
+            vec!["Rectangle1:0".to_owned()],
+            BooleanOperation::Union,
+            false,
+        );
+        wb.add_boolean("Boolean1", union);
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 1);
+        assert!(has(&realization.solids["Ext1:0"], 10.0, 10.0, 30.0));
+    }
+
+    #[test]
+    fn boolean_with_two_tools() {
+        let has = |solid: &Solid, x: f64, y: f64, z: f64| {
+            solid
+                .vertices
+                .iter()
+                .any(|v| (v.x - x).abs() < 1e-6 && (v.y - y).abs() < 1e-6 && (v.z - z).abs() < 1e-6)
+        };
+        // two posts coming down from 30, each overlapping the block and each other
+        let posts_workbench = |operation: BooleanOperation| {
+            let mut wb = block_workbench();
+            add_post(&mut wb, "PostA", (0.0, 0.0), (30.0, 30.0), 20.0);
+            add_post(&mut wb, "PostB", (10.0, 10.0), (40.0, 40.0), 20.0);
+            let boolean = Boolean::new(
+                "Ext1:0".to_owned(),
+                vec!["PostA:0".to_owned(), "PostB:0".to_owned()],
+                operation,
+                false,
+            );
+            wb.add_boolean("Boolean1", boolean);
+            wb.realize(1000)
+        };
+
+        // both posts join the block
+        let realization = posts_workbench(BooleanOperation::Union);
+        assert_eq!(realization.solids.len(), 1);
+        let solid = &realization.solids["Ext1:0"];
+        assert_eq!(solid.truck_solid.boundaries().len(), 1);
+        assert!(has(solid, 0.0, 0.0, 30.0));
+        assert!(has(solid, 40.0, 40.0, 30.0));
+
+        // only what the block and both posts have in common is left
+        let realization = posts_workbench(BooleanOperation::Intersect);
+        assert_eq!(realization.solids.len(), 1);
+        let solid = &realization.solids["Ext1:0"];
+        assert!(solid.vertices.iter().all(|v| v.x > 10.0 - 1e-6
+            && v.x < 30.0 + 1e-6
+            && v.y > 10.0 - 1e-6
+            && v.y < 30.0 + 1e-6
+            && v.z > 10.0 - 1e-6
+            && v.z < 25.0 + 1e-6));
+        assert!(has(solid, 10.0, 10.0, 10.0));
+        assert!(has(solid, 30.0, 30.0, 25.0));
+    }
+
+    #[test]
+    fn union_with_tool_that_misses() {
+        let mut wb = block_workbench();
+        add_post(&mut wb, "Far", (50.0, 50.0), (60.0, 60.0), 10.0);
+        let union = Boolean::new(
+            "Ext1:0".to_owned(),
+            vec!["Far:0".to_owned()],
+            BooleanOperation::Union,
+            false,
+        );
+        wb.add_boolean("Boolean1", union);
+
+        // the tool is used up all the same, and comes back as a second body of the block
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 2);
+        assert!(!realization.solids.contains_key("Far:0"));
+        let block = &realization.solids["Ext1:0"];
+        let far = &realization.solids["Ext1:0:1"];
+        assert!(block.vertices.iter().all(|v| v.x < 40.0 + 1e-6));
+        assert!(far.vertices.iter().all(|v| v.x > 50.0 - 1e-6));
+    }
+
+    // A rectangular post named name, extruded down from the Above plane
+    fn add_post(wb: &mut Workbench, name: &str, low: (f64, f64), high: (f64, f64), length: f64) {
+        let above_id = wb.plane_name_to_id("Above").unwrap();
+        let sketch_id = wb.add_sketch_to_plane(name, &above_id);
+        let s = wb.get_sketch_mut(name).unwrap();
+        let ll = s.add_point(low.0, low.1);
+        let lr = s.add_point(high.0, low.1);
+        let ul = s.add_point(low.0, high.1);
+        let ur = s.add_point(high.0, high.1);
+        s.add_segment(ll, lr);
+        s.add_segment(lr, ur);
+        s.add_segment(ur, ul);
+        s.add_segment(ul, ll);
+
+        let extrusion = Extrusion::new(
+            sketch_id,
+            vec![0],
+            length,
+            0.0,
+            Direction::NegativeNormal,
+            ExtrusionMode::New,
+        );
+        wb.add_extrusion(name, extrusion);
+    }
+
+    #[test]
+    fn make_workbench_with_transforms() {
+        let mut wb = block_workbench();
+        wb.add_point("From", Point3::new(40.0, 40.0, 25.0));
+        wb.add_point("To", Point3::new(100.0, 0.0, 0.0));
+
+        // a copy lifted above the block, then turned a quarter about the z axis
+        let lift = Motion::Translate(Vector3::new(0.0, 0.0, 50.0));
+        wb.add_transform(
+            "Transform1",
+            Transform::new(vec!["Ext1:0".to_owned()], lift, true),
+        );
+        let turn = Motion::Rotate {
+            axis: PatternAxis::PlaneNormal {
+                plane_id: "Plane-2".to_owned(),
+                point_id: None,
+            },
+            angle: PI / 2.0,
+        };
+        wb.add_transform(
+            "Transform2",
+            Transform::new(vec!["Transform1:0".to_owned()], turn, false),
+        );
+
+        // the original slides over so that its top corner sits on the other point
+        let align = Motion::Align {
+            from_point_id: "Point-1".to_owned(),
+            to_point_id: "Point-2".to_owned(),
+        };
+        wb.add_transform(
+            "Transform3",
+            Transform::new(vec!["Ext1:0".to_owned()], align, false),
+        );
+
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 2);
+
+        let has = |solid: &Solid, x: f64, y: f64, z: f64| {
+            solid
+                .vertices
+                .iter()
+                .any(|v| (v.x - x).abs() < 1e-6 && (v.y - y).abs() < 1e-6 && (v.z - z).abs() < 1e-6)
+        };
+        let copy = &realization.solids["Transform1:0"];
+        assert!(copy
+            .vertices
+            .iter()
+            .all(|v| v.x < 1e-6 && v.z > 50.0 - 1e-6));
+        assert!(has(copy, -40.0, 40.0, 75.0));
+        let moved = &realization.solids["Ext1:0"];
+        assert!(has(moved, 100.0, 0.0, 0.0));
+        assert!(has(moved, 60.0, -40.0, -25.0));
+    }
+
+    #[test]
+    fn transform_copy_keeps_holes() {
+        let mut wb = block_workbench();
+        let lid = Plane::new(
+            Point3::new(0.0, 0.0, 25.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        );
+        let lid_id = wb.add_plane("Lid", lid);
+        let sketch_id = wb.add_sketch_to_plane("Hole Centers", &lid_id);
+        let s = wb.get_sketch_mut("Hole Centers").unwrap();
+        let center = s.add_point(10.0, 10.0);
+        let hole = Hole::new(
+            sketch_id,
+            vec![center],
+            3.4,
+            HoleDepth::ThroughAll,
+            HoleProfile::Simple,
+            None,
+            vec!["Ext1:0".to_owned()],
+        );
+        wb.add_hole("Hole1", hole);
+
+        let shift = Motion::Translate(Vector3::new(100.0, 0.0, 0.0));
+        wb.add_transform(
+            "Transform1",
+            Transform::new(vec!["Ext1:0".to_owned()], shift, true),
+        );
+
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 2);
+        let copy = &realization.solids["Transform1:0"];
+        assert_eq!(copy.holes.len(), 1);
+        assert!((copy.holes[0].center.x - 110.0).abs() < 1e-6);
+        assert!((copy.holes[0].center.y - 10.0).abs() < 1e-6);
+        assert_eq!(realization.solids["Ext1:0"].holes.len(), 1);
+    }
+
+    #[test]
+    fn mirror_copy_keeps_holes() {
+        let mut wb = block_workbench();
+        let lid = Plane::new(
+            Point3::new(0.0, 0.0, 25.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        );
+        let lid_id = wb.add_plane("Lid", lid);
+        let sketch_id = wb.add_sketch_to_plane("Hole Centers", &lid_id);
+        let s = wb.get_sketch_mut("Hole Centers").unwrap();
+        let center = s.add_point(10.0, 10.0);
+        let hole = Hole::new(
+            sketch_id,
+            vec![center],
+            3.4,
+            HoleDepth::ThroughAll,
+            HoleProfile::Simple,
+            None,
+            vec!["Ext1:0".to_owned()],
+        );
+        wb.add_hole("Hole1", hole);
+
+        let far = Plane::new(
+            Point3::new(50.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(1.0, 0.0, 0.0),
+        );
+        let far_id = wb.add_plane("Far", far);
+        let mirror = Mirror::new(
+            vec![PatternSource::Solid("Ext1:0".to_owned())],
+            MirrorPlane::Plane(far_id),
+            false,
+        );
+        wb.add_mirror("Mirror1", mirror);
+
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 2);
+        let copy = &realization.solids["Mirror1:0"];
+        assert_eq!(copy.holes.len(), 1);
+        assert!((copy.holes[0].center.x - 90.0).abs() < 1e-6);
+        assert!((copy.holes[0].center.y - 10.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn make_workbench_with_offset_faces() {
+        let mut wb = block_workbench();
+        let raise = OffsetFace::new(
+            "Ext1:0".to_owned(),
+            vec![Vector3::new(0.0, 0.0, 1.0)],
+            vec![],
+            5.0,
+        );
+        wb.add_offset_face("Raise", raise);
+        let trim = OffsetFace::new(
+            "Ext1:0".to_owned(),
+            vec![Vector3::new(1.0, 0.0, 0.0)],
+            vec![],
+            -10.0,
+        );
+        wb.add_offset_face("Trim", trim);
+
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 1);
+
+        let solid = &realization.solids["Ext1:0"];
+        assert!(solid
+            .vertices
+            .iter()
+            .all(|v| v.x < 30.0 + 1e-6 && v.z < 30.0 + 1e-6));
+        assert!(solid.vertices.iter().any(|v| {
+            (v.x - 30.0).abs() < 1e-6 && (v.y - 40.0).abs() < 1e-6 && (v.z - 30.0).abs() < 1e-6
+        }));
+    }
+
+    #[test]
+    fn offset_face_after_boolean() {
+        let has = |solid: &Solid, x: f64, y: f64, z: f64| {
+            solid
+                .vertices
+                .iter()
+                .any(|v| (v.x - x).abs() < 1e-6 && (v.y - y).abs() < 1e-6 && (v.z - z).abs() < 1e-6)
+        };
+        let raise_top = |near: Option<Point3>| {
+            // a post joined onto the block stands 5 above its top
+            let mut wb = block_workbench();
+            add_post(&mut wb, "Post", (10.0, 10.0), (30.0, 30.0), 20.0);
+            let union = Boolean::new(
+                "Ext1:0".to_owned(),
+                vec!["Post:0".to_owned()],
+                BooleanOperation::Union,
+                false,
+            );
+            wb.add_boolean("Boolean1", union);
+            let raise = OffsetFace::new(
+                "Ext1:0".to_owned(),
+                vec![Vector3::new(0.0, 0.0, 1.0)],
+                vec![near],
+                5.0,
+            );
+            wb.add_offset_face("Raise", raise);
+            wb.realize(1000)
+        };
+
+        // the block's top and the post's top both face up, so one has to be picked
+        let realization = raise_top(None);
+        assert!(has(&realization.solids["Ext1:0"], 10.0, 10.0, 30.0));
+
+        let realization = raise_top(Some(Point3::new(20.0, 20.0, 30.0)));
+        let solid = &realization.solids["Ext1:0"];
+        assert!(has(solid, 10.0, 10.0, 35.0));
+        assert!(has(solid, 0.0, 0.0, 25.0));
+        assert!(!solid.vertices.iter().any(|v| (v.z - 30.0).abs() < 1e-6));
+
+        // two posts side by side join with their tops split along the seam, and the two
+        // halves still move as one face
+        let mut wb = block_workbench();
+        add_post(&mut wb, "Left", (50.0, 0.0), (70.0, 20.0), 10.0);
+        add_post(&mut wb, "Right", (70.0, 0.0), (90.0, 20.0), 10.0);
+        let union = Boolean::new(
+            "Left:0".to_owned(),
+            vec!["Right:0".to_owned()],
+            BooleanOperation::Union,
+            false,
+        );
+        wb.add_boolean("Boolean1", union);
+        let raise = OffsetFace::new(
+            "Left:0".to_owned(),
+            vec![Vector3::new(0.0, 0.0, 1.0)],
+            vec![],
+            5.0,
+        );
+        wb.add_offset_face("Raise", raise);
+        let realization = wb.realize(1000);
+        let posts = &realization.solids["Left:0"];
+        assert!(has(posts, 50.0, 0.0, 35.0));
+        assert!(has(posts, 90.0, 20.0, 35.0));
+        assert!(!posts.vertices.iter().any(|v| (v.z - 30.0).abs() < 1e-6));
+    }
+
+    #[test]
+    fn make_workbench_with_derived_planes() {
+        let mut wb = block_workbench();
+        wb.add_point("A", Point3::new(0.0, 0.0, 0.0));
+        wb.add_point("B", Point3::new(10.0, 0.0, 0.0));
+        wb.add_point("C", Point3::new(0.0, 10.0, 10.0));
+
+        // the block gets 5 taller before the sketch above its top is placed
+        let raise = OffsetFace::new(
+            "Ext1:0".to_owned(),
+            vec![Vector3::new(0.0, 0.0, 1.0)],
+            vec![],
+            5.0,
+        );
+        wb.add_offset_face("Raise", raise);
+        let top = || {
+            Box::new(PlaneDescription::SolidFace {
+                solid_id: "Ext1:0".to_owned(),
+                normal: Vector3::new(0.0, 0.0, 1.0),
+                near: None,
+            })
+        };
+        let above = PlaneDescription::Offset {
+            base: top(),
+            distance: 5.0,
+        };
+        wb.add_sketch_to_derived_plane("Above Top", above);
+
+        let middle = PlaneDescription::Midplane {
+            first: Box::new(PlaneDescription::SolidFace {
+                solid_id: "Ext1:0".to_owned(),
+                normal: Vector3::new(1.0, 0.0, 0.0),
+                near: None,
+            }),
+            second: Box::new(PlaneDescription::SolidFace {
+                solid_id: "Ext1:0".to_owned(),
+                normal: Vector3::new(-1.0, 0.0, 0.0),
+                near: None,
+            }),
+        };
+        wb.add_sketch_to_derived_plane("Middle", middle);
+
+        let slanted = PlaneDescription::ThreePoints {
+            first: "Point-1".to_owned(),
+            second: "Point-2".to_owned(),
+            third: "Point-3".to_owned(),
+        };
+        wb.add_sketch_to_derived_plane("Slanted", slanted);
+
+        // the top plane stood on end by turning it about the front plane's normal
+        let standing = PlaneDescription::Angled {
+            base: Box::new(PlaneDescription::PlaneId("Plane-2".to_owned())),
+            axis: PatternAxis::PlaneNormal {
+                plane_id: "Plane-0".to_owned(),
+                point_id: None,
+            },
+            angle: PI / 2.0,
+        };
+        wb.add_sketch_to_derived_plane("Standing", standing);
+
+        let across = PlaneDescription::NormalToLine {
+            sketch_id: "Sketch-0".to_owned(),
+            line_id: 1,
+            at: 0.25,
+        };
+        wb.add_sketch_to_derived_plane("Across", across);
+
+        let realization = wb.realize(1000);
+        let plane = |sketch_name: &str| {
+            &realization.planes[&format!("derived_plane_for:{}", sketch_name)].plane
+        };
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-6;
+
+        assert!(close(plane("Above Top").origin.z, 35.0));
+        assert!(close(plane("Above Top").tertiary.z, 1.0));
+
+        assert!(close(plane("Middle").origin.x, 20.0));
+        assert!(close(plane("Middle").tertiary.x, 1.0));
+
+        let slanted = plane("Slanted");
+        assert!(close(slanted.tertiary.y, -(0.5f64.sqrt())));
+        assert!(close(slanted.tertiary.z, 0.5f64.sqrt()));
+
+        let standing = plane("Standing");
+        assert!(close(standing.tertiary.x, -1.0));
+        assert!(close(standing.tertiary.z, 0.0));
+
+        // the block's first edge runs along x from the origin
+        let across = plane("Across");
+        assert!(close(across.origin.x, 10.0));
+        assert!(close(across.origin.y, 0.0));
+        assert!(close(across.tertiary.x, 1.0));
+    }
+
+    #[test]
+    fn derived_planes_as_references() {
+        // a post joined onto the block gives it two tops at different heights
+        let mut wb = block_workbench();
+        add_post(&mut wb, "Post", (10.0, 10.0), (30.0, 30.0), 20.0);
+        let union = Boolean::new(
+            "Ext1:0".to_owned(),
+            vec!["Post:0".to_owned()],
+            BooleanOperation::Union,
+            false,
+        );
+        wb.add_boolean("Boolean1", union);
+
+        let top = |near: Point3| PlaneDescription::SolidFace {
+            solid_id: "Ext1:0".to_owned(),
+            normal: Vector3::new(0.0, 0.0, 1.0),
+            near: Some(near),
+        };
+        let post_top = wb.add_derived_plane("Post Top", top(Point3::new(20.0, 20.0, 30.0)));
+        let block_top = wb.add_derived_plane("Block Top", top(Point3::new(0.0, 0.0, 25.0)));
+        let across = PlaneDescription::NormalToEdge {
+            solid_id: "Ext1:0".to_owned(),
+            edge: EdgeDescription {
+                start: Point3::new(0.0, 0.0, 0.0),
+                end: Point3::new(40.0, 0.0, 0.0),
+            },
+            at: 0.25,
+        };
+        let across = wb.add_derived_plane("Across", across);
+
+        // a derived plane can be used anywhere a plane id is taken
+        let cut = PlaneDescription::Offset {
+            base: Box::new(PlaneDescription::PlaneId("Plane-2".to_owned())),
+            distance: 10.0,
+        };
+        let cut = wb.add_derived_plane("Cut", cut);
+        let split = Split::new("Ext1:0".to_owned(), SplitTool::Plane(cut), SplitKeep::Both);
+        wb.add_split("Split1", split);
+
+        let realization = wb.realize(1000);
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-6;
+        assert!(close(realization.planes[&post_top].plane.origin.z, 30.0));
+        assert!(close(realization.planes[&block_top].plane.origin.z, 25.0));
+        let across = &realization.planes[&across].plane;
+        assert!(close(across.origin.x, 10.0));
+        assert!(close(across.tertiary.x, 1.0));
+
+        assert_eq!(realization.solids.len(), 2);
+        let front = &realization.solids["Split1:0"];
+        let back = &realization.solids["Split1:1"];
+        assert!(front.vertices.iter().all(|v| v.z > 10.0 - 1e-6));
+        assert!(back.vertices.iter().all(|v| v.z < 10.0 + 1e-6));
+    }
+
+    #[test]
+    fn make_workbench_with_axes() {
+        let mut wb = block_workbench();
+        wb.add_point("Low", Point3::new(40.0, 0.0, 0.0));
+        wb.add_point("High", Point3::new(40.0, 0.0, 25.0));
+
+        // a round post of radius 5 standing on top of the block
+        let above_id = wb.plane_name_to_id("Above").unwrap();
+        let post_id = wb.add_sketch_to_plane("Post", &above_id);
+        let s = wb.get_sketch_mut("Post").unwrap();
+        let center = s.add_point(10.0, 30.0);
+        s.add_circle(center, 5.0);
+        let post = Extrusion::new(
+            post_id,
+            vec![0],
+            10.0,
+            0.0,
+            Direction::Normal,
+            ExtrusionMode::New,
+        );
+        wb.add_extrusion("Post", post);
+
+        let corner = wb.add_axis(
+            "Corner",
+            Axis::TwoPoints {
+                first: "Point-1".to_owned(),
+                second: "Point-2".to_owned(),
+            },
+        );
+        let edge = wb.add_axis(
+            "Edge",
+            Axis::SketchLine {
+                sketch_id: "Sketch-0".to_owned(),
+                line_id: 1,
+            },
+        );
+        let z = wb.add_axis(
+            "Z",
+            Axis::PlaneIntersection {
+                first: "Plane-0".to_owned(),
+                second: "Plane-1".to_owned(),
+            },
+        );
+        let round = wb.add_axis(
+            "Round",
+            Axis::CylindricalFace {
+                solid_id: "Post:0".to_owned(),
+                point: Point3::new(15.0, 30.0, 35.0),
+            },
+        );
+
+        // the block turns a quarter about the z axis
+        let turn = Motion::Rotate {
+            axis: PatternAxis::Axis(z.clone()),
+            angle: PI / 2.0,
+        };
+        wb.add_transform(
+            "Turn",
+            Transform::new(vec!["Ext1:0".to_owned()], turn, false),
+        );
+
+        let realization = wb.realize(1000);
+        assert_eq!(realization.axes.len(), 4);
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-6;
+
+        let corner = &realization.axes[&corner];
+        assert!(close(corner.origin.x, 40.0) && close(corner.direction.z, 1.0));
+        let edge = &realization.axes[&edge];
+        assert!(close(edge.origin.y, 0.0) && close(edge.direction.x, 1.0));
+        let z = &realization.axes[&z];
+        assert!(close(z.origin.x, 0.0) && close(z.origin.y, 0.0) && close(z.direction.z, 1.0));
+
+        let round = &realization.axes[&round];
+        assert!(close(round.origin.x, 10.0) && close(round.origin.y, 30.0));
+        assert!(close(round.direction.z.abs(), 1.0));
+
+        let block = &realization.solids["Ext1:0"];
+        assert!(block
+            .vertices
+            .iter()
+            .any(|v| close(v.x, -40.0) && close(v.y, 40.0) && close(v.z, 25.0)));
+    }
+
+    #[test]
+    fn revolve_about_axis_step() {
+        let mut wb = Workbench::new("Test Workbench");
+        wb.add_sketch_to_plane("Sketch 1", "Plane-0");
+        let s = wb.get_sketch_mut("Sketch 1").unwrap();
+        let ll = s.add_point(10.0, 0.0);
+        let lr = s.add_point(20.0, 0.0);
+        let ul = s.add_point(10.0, 5.0);
+        let ur = s.add_point(20.0, 5.0);
+        s.add_segment(ll, lr);
+        s.add_segment(lr, ur);
+        s.add_segment(ur, ul);
+        s.add_segment(ul, ll);
+
+        // the z axis, where the Front and Right planes cross
+        let z = wb.add_axis(
+            "Z",
+            Axis::PlaneIntersection {
+                first: "Plane-0".to_owned(),
+                second: "Plane-1".to_owned(),
+            },
+        );
+        let revolve = Revolve::new(
+            "Sketch-0".to_owned(),
+            vec![0],
+            RevolveAxis::Axis(z),
+            PI,
+            ExtrusionMode::New,
+        );
+        wb.add_revolve("Rev1", revolve);
+
+        // half a turn carries the profile round to the far side of the z axis
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 1);
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-6;
+        let vertices = &realization.solids["Rev1:0"].vertices;
+        assert!(vertices
+            .iter()
+            .any(|v| close(v.x, -20.0) && close(v.y, 0.0) && close(v.z, 5.0)));
+        assert!(vertices
+            .iter()
+            .all(|v| v.x.hypot(v.y) > 10.0 - 1e-6 && v.x.hypot(v.y) < 20.0 + 1e-6));
+    }
+
+    #[test]
+    fn make_workbench_with_derived_points() {
+        let mut wb = block_workbench();
+
+        // a round post of radius 5 standing on top of the block
+        let above_id = wb.plane_name_to_id("Above").unwrap();
+        let post_id = wb.add_sketch_to_plane("Post", &above_id);
+        let s = wb.get_sketch_mut("Post").unwrap();
+        let center = s.add_point(10.0, 30.0);
+        s.add_circle(center, 5.0);
+        let post = Extrusion::new(
+            post_id,
+            vec![0],
+            10.0,
+            0.0,
+            Direction::Normal,
+            ExtrusionMode::New,
+        );
+        wb.add_extrusion("Post", post);
+        let z = wb.add_axis(
+            "Z",
+            Axis::PlaneIntersection {
+                first: "Plane-0".to_owned(),
+                second: "Plane-1".to_owned(),
+            },
+        );
+
+        let sketch_corner = wb.add_derived_point(
+            "Sketch Corner",
+            PointDescription::SketchPoint {
+                sketch_id: "Sketch-0".to_owned(),
+                point_id: 4,
+            },
+        );
+        let top_corner = wb.add_derived_point(
+            "Top Corner",
+            PointDescription::SolidVertex {
+                solid_id: "Ext1:0".to_owned(),
+                near: Point3::new(41.0, 39.0, 26.0),
+            },
+        );
+        let front_middle = wb.add_derived_point(
+            "Front Middle",
+            PointDescription::EdgeMidpoint {
+                solid_id: "Ext1:0".to_owned(),
+                near: Point3::new(20.0, -1.0, -1.0),
+            },
+        );
+        let post_center = wb.add_derived_point(
+            "Post Center",
+            PointDescription::CircleCenter {
+                solid_id: "Post:0".to_owned(),
+                near: Point3::new(15.0, 30.0, 40.0),
+            },
+        );
+        let crossing = wb.add_derived_point(
+            "Crossing",
+            PointDescription::AxisPlane {
+                axis_id: z,
+                plane_id: above_id,
+            },
+        );
+
+        // derived points can define planes just like fixed ones
+        let slanted = PlaneDescription::ThreePoints {
+            first: sketch_corner.clone(),
+            second: top_corner.clone(),
+            third: front_middle.clone(),
+        };
+        wb.add_sketch_to_derived_plane("Slanted", slanted);
+
+        let realization = wb.realize(1000);
+        let at = |point_id: &str, x: f64, y: f64, z: f64| {
+            let p = &realization.points[point_id];
+            (p.x - x).abs() < 1e-6 && (p.y - y).abs() < 1e-6 && (p.z - z).abs() < 1e-6
+        };
+        assert!(at(&sketch_corner, 40.0, 40.0, 0.0));
+        assert!(at(&top_corner, 40.0, 40.0, 25.0));
+        assert!(at(&front_middle, 20.0, 0.0, 0.0));
+        assert!(at(&post_center, 10.0, 30.0, 40.0));
+        assert!(at(&crossing, 0.0, 0.0, 30.0));
+
+        let slanted = &realization.planes["derived_plane_for:Slanted"].plane;
+        assert!((slanted.origin.x - 40.0).abs() < 1e-6 && (slanted.origin.y - 40.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn make_workbench_with_holes() {
+        let mut wb = block_workbench();
+
+        // a plane flush with the top of the block, to put the hole centers on
+        let lid = Plane::new(
+            Point3::new(0.0, 0.0, 25.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        );
+        let lid_id = wb.add_plane("Lid", lid);
+        let sketch_id = wb.add_sketch_to_plane("Hole Centers", &lid_id);
+        let s = wb.get_sketch_mut("Hole Centers").unwrap();
+        let a = s.add_point(10.0, 10.0);
+        let b = s.add_point(30.0, 30.0);
+        let c = s.add_point(30.0, 10.0);
+        let d = s.add_point(10.0, 30.0);
+
+        let hole = Hole::new(
+            sketch_id.clone(),
+            vec![a, b],
+            3.4,
+            HoleDepth::ThroughAll,
+            HoleProfile::Counterbore {
+                diameter: 6.5,
+                depth: 3.0,
+            },
+            Some("M3x0.5".to_owned()),
+            vec!["Ext1:0".to_owned()],
+        );
+        wb.add_hole("Mounting Holes", hole);
+
+        // a 90 degree countersink that is 1.7 deep, on a hole 10 deep
+        let hole = Hole::new(
+            sketch_id.clone(),
+            vec![c],
+            3.4,
+            HoleDepth::Blind { depth: 10.0 },
+            HoleProfile::Countersink {
+                diameter: 6.8,
+                angle: PI / 2.0,
+            },
+            None,
+            vec!["Ext1:0".to_owned()],
+        );
+        wb.add_hole("Countersunk Hole", hole);
+
+        // the same countersink can't fit on a hole only 1 deep
+        let hole = Hole::new(
+            sketch_id,
+            vec![d],
+            3.4,
+            HoleDepth::Blind { depth: 1.0 },
+            HoleProfile::Countersink {
+                diameter: 6.8,
+                angle: PI / 2.0,
+            },
+            None,
+            vec!["Ext1:0".to_owned()],
+        );
+        wb.add_hole("Shallow Hole", hole);
+
+        let realization = wb.realize(1000);
+        assert_eq!(realization.solids.len(), 1);
+
+        let solid = &realization.solids["Ext1:0"];
+        let holes = &solid.holes;
+        assert_eq!(holes.len(), 3);
+        assert_eq!(holes[0].thread, Some("M3x0.5".to_owned()));
+        assert!(holes[0].depth.is_none());
+        assert_eq!(holes[2].depth, Some(10.0));
+
+        // (distance from the hole's axis, height) of every vertex near a hole center
+        let around = |x: f64, y: f64| -> Vec<(f64, f64)> {
+            solid
+                .vertices
+                .iter()
+                .map(|v| ((v.x - x).hypot(v.y - y), v.z))
+                .filter(|(r, _)| *r < 5.0)
+                .collect()
+        };
+        let has = |vertices: &Vec<(f64, f64)>, r: f64, z: f64| {
+            vertices
+                .iter()
+                .any(|(vr, vz)| (vr - r).abs() < 1e-3 && (vz - z).abs() < 1e-3)
+        };
+
+        // the counterbore is 6.5 across with a flat floor 3 down, where the hole narrows
+        let counterbore = around(10.0, 10.0);
+        assert!(has(&counterbore, 3.25, 25.0));
+        assert!(has(&counterbore, 3.25, 22.0));
+        assert!(has(&counterbore, 1.7, 22.0));
+        assert!(!counterbore
+            .iter()
+            .any(|(r, z)| *r < 3.24 && *z > 22.0 + 1e-6));
+
+        // the countersink narrows from 6.8 across at the top to the hole at 1.7 down, and
+        // the hole stops 10 down
+        let countersink = around(30.0, 10.0);
+        assert!(has(&countersink, 3.4, 25.0));
+        assert!(has(&countersink, 1.7, 23.3));
+        assert!(has(&countersink, 1.7, 15.0));
+        assert!(!countersink
+            .iter()
+            .any(|(r, z)| *z > 23.3 + 1e-6 && *r < 1.7 + (*z - 23.3) - 1e-3));
+
+        // nothing was drilled where the shallow hole would have gone
+        assert!(!around(10.0, 30.0).iter().any(|(r, _)| *r < 3.5));
+    }
 }